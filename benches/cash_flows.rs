@@ -0,0 +1,59 @@
+//! NPV and IRR of 10,000-period cash flows, against the former evaluation with one `powf` per period
+//!
+//! Run with `cargo bench --bench cash_flows`
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rfinancial::*;
+
+const PERIODS: usize = 10_000;
+
+fn values() -> Vec<f64> {
+    let mut values = vec![1.0; PERIODS];
+    values[0] = -6321.0;
+    values
+}
+
+// the former `NetPresentValue::npv`
+fn npv_powf(values: &[f64], rate: f64) -> f64 {
+    values
+        .iter()
+        .enumerate()
+        .map(|(p, &c)| c * (1.0 + rate).powf(-(p as f64)))
+        .sum()
+}
+
+fn bench_npv(c: &mut Criterion) {
+    let values = values();
+    let mut group = c.benchmark_group("npv");
+    group.bench_function("powf", |b| {
+        b.iter(|| npv_powf(black_box(&values), black_box(0.0001)))
+    });
+    for summation in [Summation::Horner, Summation::Compensated] {
+        group.bench_function(format!("{:?}", summation), |b| {
+            b.iter(|| {
+                NetPresentValue::from_slice((black_box(&values), black_box(0.0001)))
+                    .with_summation(summation)
+                    .get()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_irr(c: &mut Criterion) {
+    // up to 100 iterations of Newton's method, each evaluating the polynomial and its derivative
+    let values = values();
+    let mut group = c.benchmark_group("irr");
+    for summation in [Summation::Horner, Summation::Compensated] {
+        group.bench_function(format!("{:?}", summation), |b| {
+            b.iter(|| {
+                InternalRateReturn::from_slice(black_box(&values))
+                    .with_summation(summation)
+                    .get()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_npv, bench_irr);
+criterion_main!(benches);
@@ -0,0 +1,110 @@
+// Generated by `rfinancial::capi::header`, do not edit
+#ifndef RFINANCIAL_H
+#define RFINANCIAL_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/// Status code returned by every function
+typedef int32_t rf_status;
+
+/// Success
+#define RF_OK 0
+/// An invalid parameter
+#define RF_PARA_ERROR 1
+/// An instance cannot be constructed
+#define RF_CONSTRUCTOR_ERROR 2
+/// Any other error
+#define RF_OTHER_ERROR 3
+/// A null pointer where a value is required
+#define RF_NULL_POINTER 4
+/// An output buffer too small for the result
+#define RF_BUFFER_TOO_SMALL 5
+/// A panic caught before it unwinds into the caller
+#define RF_PANIC 6
+
+/// One period of an amortization schedule
+typedef struct rf_amortization_row {
+    uint32_t per;
+    double rate;
+    double pmt;
+    double ipmt;
+    double ppmt;
+    double balance;
+} rf_amortization_row;
+
+/// Future value, see `FutureValue`
+rf_status rf_fv(double rate, uint32_t nper, double pmt, double pv, int32_t when, double *out);
+
+/// Present value, see `PresentValue`
+rf_status rf_pv(double rate, uint32_t nper, double pmt, double fv, int32_t when, double *out);
+
+/// Payment against loan principal plus interest, see `Payment`
+rf_status rf_pmt(double rate, uint32_t nper, double pv, double fv, int32_t when, double *out);
+
+/// Number of periodic payments, see `NumberPeriod`
+rf_status rf_nper(double rate, double pmt, double pv, double fv, int32_t when, double *out, bool *has_value);
+
+/// Interest portion of payment `per`, see `InterestPayment`
+rf_status rf_ipmt(double rate, uint32_t per, uint32_t nper, double pv, double fv, int32_t when, double *out, bool *has_value);
+
+/// Principal portion of payment `per`, see `PrincipalPayment`
+rf_status rf_ppmt(double rate, uint32_t per, uint32_t nper, double pv, double fv, int32_t when, double *out, bool *has_value);
+
+/// Rate of interest per period, see `Rate`
+rf_status rf_rate(uint32_t nper, double pmt, double pv, double fv, int32_t when, double guess, double tol, uint32_t maxiter, double *out, bool *has_value);
+
+/// Internal rate of return, see `InternalRateReturn`
+rf_status rf_irr(const double *values, size_t len, double *out, bool *has_value);
+
+/// Net present value, see `NetPresentValue`
+rf_status rf_npv(const double *values, size_t len, double rate, double *out);
+
+/// Modified internal rate of return, see `ModifiedIRR`
+rf_status rf_mirr(const double *values, size_t len, double finance_rate, double reinvest_rate, double *out, bool *has_value);
+
+/// Future value with a rate for each period, see `FutureValueSchedule`
+rf_status rf_fvschedule(const double *rates, size_t len, double pmt, double pv, int32_t when, double *out);
+
+/// Present value with a rate for each period, see `PresentValueSchedule`
+rf_status rf_pvschedule(const double *rates, size_t len, double pmt, double fv, int32_t when, double *out);
+
+/// Net present value with a rate for each period, see `NetPresentValueSchedule`
+rf_status rf_npvschedule(const double *values, size_t values_len, const double *rates, size_t rates_len, double *out);
+
+/// Future value of an irregular stream of payments, see `FutureValueStream`
+rf_status rf_fvstream(double rate, const double *pmts, size_t len, double pv, int32_t when, double *out);
+
+/// Payback period, see `PaybackPeriod`
+rf_status rf_payback(const double *values, size_t len, double *out, bool *has_value);
+
+/// Discounted payback period, see `DiscountedPaybackPeriod`
+rf_status rf_discounted_payback(const double *values, size_t len, double rate, double *out, bool *has_value);
+
+/// Profitability index, see `ProfitabilityIndex`
+rf_status rf_pi(const double *values, size_t len, double rate, double *out, bool *has_value);
+
+/// Benefit-cost ratio, see `BenefitCostRatio`
+rf_status rf_bcr(const double *values, size_t len, double rate, double *out, bool *has_value);
+
+/// Equivalent annual annuity, see `EquivalentAnnualAnnuity`
+rf_status rf_eaa(const double *values, size_t len, double rate, double *out, bool *has_value);
+
+/// Equivalent annual cost, see `EquivalentAnnualCost`
+rf_status rf_eac(const double *values, size_t len, double rate, double *out, bool *has_value);
+
+/// Amortization schedule of an adjustable-rate loan, see `AdjustableRateLoan`.
+/// The number of rows is written to `written`, and if `rows_len` is too small
+/// `RF_BUFFER_TOO_SMALL` is returned with the number of rows required
+rf_status rf_arm(double rate, uint32_t nper, double pv, double fv, int32_t when, uint32_t first_reset, uint32_t reset_freq, const double *index, size_t index_len, double margin, double periodic_cap, double lifetime_cap, double lifetime_floor, rf_amortization_row *rows, size_t rows_len, size_t *written);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // RFINANCIAL_H
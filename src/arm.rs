@@ -0,0 +1,501 @@
+#[cfg(feature = "std")]
+use crate::{get_f64, get_u32, get_vecf64, get_when, ParaMap};
+use crate::{Error, Payment, Result, WhenType};
+use alloc::vec::Vec;
+/// # Compute the amortization schedule of an adjustable-rate loan
+///
+/// The loan starts at `rate` and is fixed for `first_reset` periods. After that the rate resets every
+/// `reset_freq` periods to `index + margin`, limited by the caps and floors, and the remaining balance
+/// is re-amortized over the remaining term at the new rate.
+///
+/// ## Parameters
+/// * `rate` : the initial interest rate per period
+/// * `nper` : number of periodic payments over the life of the loan
+/// * `pv` : a present value, i.e. the loan amount
+/// * `fv` : a future value, i.e. the balloon left at the end of the loan
+/// * `when` : when payments are due [`WhenType`]. Defaults to `When::End`
+/// * `first_reset` : number of periods the initial rate is fixed for
+/// * `reset_freq` : number of periods between two resets after the first one
+/// * `index` : index rate per period at each reset; the last value is carried forward if there are more resets.
+///   With `margin` of `0.0` this is simply a sequence of reset rates
+/// * `margin` : spread added to `index` at each reset
+/// * `periodic_cap` : maximum change of rate, up or down, at a single reset. Use `f64::INFINITY` for no cap
+/// * `lifetime_cap` : maximum rate over the life of the loan. Use `f64::INFINITY` for no cap
+/// * `lifetime_floor` : minimum rate over the life of the loan. Use `f64::NEG_INFINITY` for no floor
+///
+/// ## Return:
+/// * `schedule`: a vector of [`AmortizationRow`], one for each period
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// // 5/1 ARM: fixed for 60 months, then resets every 12 months
+/// let arm = AdjustableRateLoan::from_tuple((
+///     0.06 / 12.0,
+///     360,
+///     250000.0,
+///     0.0,
+///     WhenType::End,
+///     60,
+///     12,
+///     vec![0.05 / 12.0, 0.055 / 12.0, 0.07 / 12.0],
+///     0.0025 / 12.0,
+///     0.02 / 12.0,
+///     0.11 / 12.0,
+///     0.03 / 12.0,
+/// ));
+/// println!("{:#?}'s schedule is {:?}", arm, arm.get());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdjustableRateLoan {
+    rate: f64,
+    nper: u32,
+    pv: f64,
+    fv: f64,
+    when: WhenType,
+    first_reset: u32,
+    reset_freq: u32,
+    index: Vec<f64>,
+    margin: f64,
+    periodic_cap: f64,
+    lifetime_cap: f64,
+    lifetime_floor: f64,
+}
+
+/// One period of an amortization schedule
+///
+/// Payments follow the sign convention of [`Payment`], while `balance` carries the sign of `pv`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmortizationRow {
+    /// the payment period, starting from 1
+    pub per: u32,
+    /// the interest rate applied in this period
+    pub rate: f64,
+    /// total payment of this period
+    pub pmt: f64,
+    /// interest portion of the payment
+    pub ipmt: f64,
+    /// principal portion of the payment
+    pub ppmt: f64,
+    /// remaining principal after the payment
+    pub balance: f64,
+}
+
+impl AdjustableRateLoan {
+    /// Instantiate an `AdjustableRateLoan` instance from a tuple of (`rate`, `nper`, `pv`, `fv`, `when`, `first_reset`,
+    /// `reset_freq`, `index`, `margin`, `periodic_cap`, `lifetime_cap` and `lifetime_floor`) in said order
+    #[allow(clippy::type_complexity)]
+    pub fn from_tuple(
+        tup: (
+            f64,
+            u32,
+            f64,
+            f64,
+            WhenType,
+            u32,
+            u32,
+            Vec<f64>,
+            f64,
+            f64,
+            f64,
+            f64,
+        ),
+    ) -> Self {
+        AdjustableRateLoan {
+            rate: tup.0,
+            nper: tup.1,
+            pv: tup.2,
+            fv: tup.3,
+            when: tup.4,
+            first_reset: tup.5,
+            reset_freq: tup.6,
+            index: tup.7,
+            margin: tup.8,
+            periodic_cap: tup.9,
+            lifetime_cap: tup.10,
+            lifetime_floor: tup.11,
+        }
+    }
+
+    /// Instantiate an `AdjustableRateLoan` instance from a hash map with keys of (`rate`, `nper`, `pv`, `fv`, `when`,
+    /// `first_reset`, `reset_freq`, `index`, `margin`, `periodic_cap`, `lifetime_cap` and `lifetime_floor`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `AdjustableRateLoan` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+
+        let rate = get_f64(&map, "rate").map_err(op)?;
+        let nper = get_u32(&map, "nper").map_err(op)?;
+        let pv = get_f64(&map, "pv").map_err(op)?;
+        let fv = get_f64(&map, "fv").map_err(op)?;
+        let when = get_when(&map, "when").map_err(op)?;
+        let first_reset = get_u32(&map, "first_reset").map_err(op)?;
+        let reset_freq = get_u32(&map, "reset_freq").map_err(op)?;
+        let index = get_vecf64(&map, "index").map_err(op)?;
+        let margin = get_f64(&map, "margin").map_err(op)?;
+        let periodic_cap = get_f64(&map, "periodic_cap").map_err(op)?;
+        let lifetime_cap = get_f64(&map, "lifetime_cap").map_err(op)?;
+        let lifetime_floor = get_f64(&map, "lifetime_floor").map_err(op)?;
+        Ok(AdjustableRateLoan {
+            rate,
+            nper,
+            pv,
+            fv,
+            when,
+            first_reset,
+            reset_freq,
+            index,
+            margin,
+            periodic_cap,
+            lifetime_cap,
+            lifetime_floor,
+        })
+    }
+
+    /// Rate of each period after applying resets, caps and floors
+    fn rates(&self) -> Result<Vec<f64>> {
+        // a NaN would reach `clamp`, which panics on a NaN bound
+        if !self.rate.is_finite() {
+            return Err(Error::ParaError("rate: must be finite".into()));
+        }
+        if !self.margin.is_finite() || self.index.iter().any(|i| !i.is_finite()) {
+            return Err(Error::ParaError("index and margin: must be finite".into()));
+        }
+        if !self.index.is_empty() && self.reset_freq == 0 {
+            return Err(Error::ParaError(
+                "reset_freq: must be positive when index is given".into(),
+            ));
+        }
+        if self.periodic_cap.is_nan() || self.periodic_cap < 0.0 {
+            return Err(Error::ParaError(
+                "periodic_cap: must be non-negative".into(),
+            ));
+        }
+        if self.lifetime_floor.is_nan()
+            || self.lifetime_cap.is_nan()
+            || self.lifetime_floor > self.lifetime_cap
+        {
+            return Err(Error::ParaError(
+                "lifetime_floor: must not exceed lifetime_cap".into(),
+            ));
+        }
+
+        let mut rates = Vec::new();
+        rates
+            .try_reserve_exact(self.nper as usize)
+            .map_err(|_| Error::OtherError("nper: too many periods to allocate".into()))?;
+        let mut current = self.rate;
+        for per in 1..=self.nper {
+            // periods elapsed since the first reset
+            let since = per as i64 - self.first_reset as i64 - 1;
+            if !self.index.is_empty() && since >= 0 && since % self.reset_freq as i64 == 0 {
+                let nth = (since / self.reset_freq as i64) as usize;
+                let index = self.index[nth.min(self.index.len() - 1)];
+                let target = index + self.margin;
+                current = target
+                    .clamp(current - self.periodic_cap, current + self.periodic_cap)
+                    .clamp(self.lifetime_floor, self.lifetime_cap);
+            }
+            rates.push(current);
+        }
+
+        Ok(rates)
+    }
+
+    fn schedule(&self) -> Result<Vec<AmortizationRow>> {
+        /*
+            Walk through the periods, re-amortizing whatever is owed over the remaining term whenever the rate changes.
+            With payments at the begining, interest accrued in a period is paid with the next payment.
+        */
+        let rates = self.rates()?;
+
+        let mut schedule = Vec::new();
+        schedule
+            .try_reserve_exact(self.nper as usize)
+            .map_err(|_| Error::OtherError("nper: too many periods to allocate".into()))?;
+        let mut balance = self.pv;
+        let mut accrued = 0.0;
+        let mut pmt = 0.0;
+        let mut last_rate = None;
+        for (p, &rate) in rates.iter().enumerate() {
+            let per = p as u32 + 1;
+            if last_rate != Some(rate) {
+                pmt = Payment::from_tuple((
+                    rate,
+                    self.nper - p as u32,
+                    balance + accrued,
+                    self.fv,
+                    self.when.clone(),
+                ))
+                .get()?;
+                last_rate = Some(rate);
+            }
+
+            let ipmt = match self.when {
+                WhenType::End => -balance * rate,
+                WhenType::Begin => -accrued,
+                _ => {
+                    return Err(Error::ParaError(
+                        "when: End or Begin for an amortization schedule".into(),
+                    ))
+                }
+            };
+            let ppmt = pmt - ipmt;
+            balance += ppmt;
+            if self.when == WhenType::Begin {
+                accrued = balance * rate;
+            }
+
+            schedule.push(AmortizationRow {
+                per,
+                rate,
+                pmt,
+                ipmt,
+                ppmt,
+                balance,
+            });
+        }
+
+        Ok(schedule)
+    }
+
+    /// Get the amortization schedule from an instance of `AdjustableRateLoan`
+    pub fn get(&self) -> Result<Vec<AmortizationRow>> {
+        self.schedule()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_arm_from_tuple() {
+        let arm = AdjustableRateLoan::from_tuple((
+            0.06 / 12.0,
+            360,
+            250000.0,
+            0.0,
+            WhenType::End,
+            60,
+            12,
+            vec![0.05 / 12.0],
+            0.0025 / 12.0,
+            0.02 / 12.0,
+            0.11 / 12.0,
+            0.03 / 12.0,
+        ));
+        let cond = (arm.rate == 0.06 / 12.0)
+            && (arm.nper == 360)
+            && (arm.pv == 250000.0)
+            && (arm.fv == 0.0)
+            && (arm.when == WhenType::End)
+            && (arm.first_reset == 60)
+            && (arm.reset_freq == 12)
+            && (arm.index == vec![0.05 / 12.0])
+            && (arm.margin == 0.0025 / 12.0)
+            && (arm.periodic_cap == 0.02 / 12.0)
+            && (arm.lifetime_cap == 0.11 / 12.0)
+            && (arm.lifetime_floor == 0.03 / 12.0);
+
+        assert!(cond);
+    }
+
+    #[test]
+    fn test_arm_from_map() {
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.06 / 12.0));
+        map.insert("nper".into(), ParaType::U32(360));
+        map.insert("pv".into(), ParaType::F64(250000.0));
+        map.insert("fv".into(), ParaType::F64(0.0));
+        map.insert("when".into(), ParaType::When(WhenType::End));
+        map.insert("first_reset".into(), ParaType::U32(60));
+        map.insert("reset_freq".into(), ParaType::U32(12));
+        map.insert("index".into(), ParaType::VecF64(vec![0.05 / 12.0]));
+        map.insert("margin".into(), ParaType::F64(0.0025 / 12.0));
+        map.insert("periodic_cap".into(), ParaType::F64(0.02 / 12.0));
+        map.insert("lifetime_cap".into(), ParaType::F64(0.11 / 12.0));
+        map.insert("lifetime_floor".into(), ParaType::F64(0.03 / 12.0));
+        let arm = AdjustableRateLoan::from_map(map).unwrap();
+        let cond = (arm.first_reset == 60)
+            && (arm.reset_freq == 12)
+            && (arm.index == vec![0.05 / 12.0])
+            && (arm.lifetime_floor == 0.03 / 12.0);
+
+        assert!(cond);
+    }
+
+    #[test]
+    fn test_arm_without_reset() {
+        // no index means a fixed-rate loan, so every row agrees with `ipmt` and `ppmt`
+        for when in [WhenType::End, WhenType::Begin] {
+            let arm = AdjustableRateLoan::from_tuple((
+                0.1 / 12.0,
+                24,
+                2000.0,
+                0.0,
+                when.clone(),
+                0,
+                0,
+                vec![],
+                0.0,
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ));
+            let schedule = arm.get().unwrap();
+            assert_eq!(schedule.len(), 24);
+            for row in schedule.iter() {
+                let tup = (0.1 / 12.0, row.per, 24, 2000.0, 0.0, when.clone());
+                let ipmt = InterestPayment::from_tuple(tup.clone())
+                    .get()
+                    .unwrap()
+                    .unwrap();
+                let ppmt = PrincipalPayment::from_tuple(tup).get().unwrap().unwrap();
+                assert!(
+                    float_close(row.ipmt, ipmt, RTOL, ATOL),
+                    "{:#?} v.s. {:#?}",
+                    row.ipmt,
+                    ipmt
+                );
+                assert!(
+                    float_close(row.ppmt, ppmt, RTOL, ATOL),
+                    "{:#?} v.s. {:#?}",
+                    row.ppmt,
+                    ppmt
+                );
+            }
+            let res = schedule.last().unwrap().balance;
+            assert!(float_close(res, 0.0, RTOL, ATOL), "{:#?} v.s. 0.0", res);
+        }
+    }
+
+    #[test]
+    fn test_arm_caps_and_floors() {
+        // resets at periods 3, 5, 7 and 9
+        let arm = AdjustableRateLoan::from_tuple((
+            0.05,
+            10,
+            10000.0,
+            0.0,
+            WhenType::End,
+            2,
+            2,
+            vec![0.09, 0.01, 0.01, 0.08],
+            0.005,
+            0.02,
+            0.08,
+            0.03,
+        ));
+        let res: Vec<f64> = arm.get().unwrap().iter().map(|row| row.rate).collect();
+        // 0.095 capped by periodic cap at 0.07
+        // 0.015 capped by periodic cap at 0.05
+        // 0.015 floored at 0.03
+        // 0.085 capped by lifetime cap at 0.08
+        let tgt = vec![0.05, 0.05, 0.07, 0.07, 0.05, 0.05, 0.03, 0.03, 0.05, 0.05];
+        for (r, t) in res.iter().zip(tgt.iter()) {
+            assert!(
+                float_close(*r, *t, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+    }
+
+    #[test]
+    fn test_arm_reamortize() {
+        let arm = AdjustableRateLoan::from_tuple((
+            0.05,
+            10,
+            10000.0,
+            0.0,
+            WhenType::End,
+            2,
+            2,
+            vec![0.065],
+            0.0,
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ));
+        let schedule = arm.get().unwrap();
+
+        // payment after the reset re-amortizes the balance after 2nd period over the remaining 8 periods
+        let balance = schedule[1].balance;
+        let tgt = Payment::from_tuple((0.065, 8, balance, 0.0, WhenType::End))
+            .get()
+            .unwrap();
+        let res = schedule[2].pmt;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        let res = schedule.last().unwrap().balance;
+        assert!(float_close(res, 0.0, RTOL, ATOL), "{:#?} v.s. 0.0", res);
+    }
+
+    #[test]
+    fn test_arm_zero_reset_freq() {
+        let arm = AdjustableRateLoan::from_tuple((
+            0.05,
+            10,
+            10000.0,
+            0.0,
+            WhenType::End,
+            2,
+            0,
+            vec![0.065],
+            0.0,
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ));
+        assert!(arm.get().is_err());
+    }
+
+    #[test]
+    fn test_arm_nan() {
+        let arm = |rate: f64, index: Vec<f64>| {
+            AdjustableRateLoan::from_tuple((
+                rate,
+                10,
+                10000.0,
+                0.0,
+                WhenType::End,
+                2,
+                1,
+                index,
+                0.0,
+                0.01,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ))
+            .get()
+        };
+        assert!(matches!(arm(f64::NAN, vec![]), Err(Error::ParaError(_))));
+        assert!(matches!(
+            arm(0.05, vec![0.06, f64::NAN, 0.07]),
+            Err(Error::ParaError(_))
+        ));
+    }
+
+    #[test]
+    fn test_arm_err() {
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.06 / 12.0));
+        map.insert("nper".into(), ParaType::U32(360));
+        map.insert("pv".into(), ParaType::F64(250000.0));
+        let arm = AdjustableRateLoan::from_map(map);
+        assert!(arm.is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! # Vectorized functions over `ndarray`
+//! Functions built with the `ndarray` feature, taking array views of any dimension and broadcasting them against
+//! each other by numpy's rules, as `numpy_financial` does. Scalars are 0-d views such as `aview0(&0.05)`.
+//! Outputs are arrays of the broadcast shape, with `NaN` where there is no solution, and an argument out of the
+//! calculator's domain fails the whole call.
+//!
+//! [`irr`], [`npv`] and [`mirr`] take a 2-D array of cash flows and compute one result per cash flow along `axis`,
+//! e.g. `Axis(1)` for a cash flow in each row.
+//!
+//! ## Example
+//! ```rust
+//! use ndarray::{array, aview0, Axis};
+//! use rfinancial::{array, WhenType};
+//! // payments of a 250,000 loan for three rates and two terms, of shape (3, 2)
+//! let rates = array![[0.04], [0.05], [0.06]] / 12.0;
+//! let nper = array![180, 360];
+//! let end = aview0(&WhenType::End);
+//! let pmts = array::pmt(rates.view(), nper.view(), aview0(&250000.0), aview0(&0.0), end);
+//! println!("{:?}", pmts);
+//!
+//! let values = array![[-100.0, 39.0, 59.0, 55.0, 20.0], [-150.0, 60.0, 60.0, 60.0, 0.0]];
+//! let npvs = array::npv(0.1, values.view(), Axis(1));
+//! println!("{:?}", npvs);
+//! ```
+use crate::{
+    Error, FutureValue, InterestPayment, InternalRateReturn, ModifiedIRR, NetPresentValue,
+    NumberPeriod, Payment, PresentValue, PrincipalPayment, Rate, Result, WhenType,
+};
+use ndarray::{Array1, ArrayD, ArrayView, ArrayView2, ArrayViewD, Axis, Dimension, IxDyn};
+
+/// Shape of arrays broadcast against each other by numpy's rules
+fn broadcast_shape(shapes: &[&[usize]]) -> Result<Vec<usize>> {
+    let ndim = shapes.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut shape = vec![1; ndim];
+    for s in shapes {
+        for (i, &n) in s.iter().enumerate() {
+            let dim = &mut shape[ndim - s.len() + i];
+            match (*dim, n) {
+                (d, n) if d == n || n == 1 => {}
+                (1, n) => *dim = n,
+                _ => {
+                    return Err(Error::ParaError(format!(
+                        "operands could not be broadcast together with shapes {:?}",
+                        shapes
+                    )))
+                }
+            }
+        }
+    }
+    Ok(shape)
+}
+
+fn broadcast<'a, T, D: Dimension>(
+    view: &'a ArrayView<'_, T, D>,
+    shape: &[usize],
+) -> Result<ArrayViewD<'a, T>> {
+    view.broadcast(IxDyn(shape)).ok_or_else(|| {
+        Error::ParaError(format!(
+            "could not broadcast shape {:?} to {:?}",
+            view.shape(),
+            shape
+        ))
+    })
+}
+
+// apply `$body` elementwise over the broadcast views `$arg`s, each bound to its element in `$body`
+macro_rules! elementwise {
+    (($($arg:ident),+) => $body:expr) => {{
+        let shape = broadcast_shape(&[$($arg.shape()),+])?;
+        $(let $arg = broadcast(&$arg, &shape)?;)+
+        $(let mut $arg = $arg.iter();)+
+        let len = shape.iter().product();
+        let out = (0..len)
+            .map(|_| {
+                // every view has `len` elements in the broadcast shape
+                $(let $arg = $arg.next().cloned().unwrap_or_default();)+
+                $body
+            })
+            .collect::<Result<Vec<f64>>>()?;
+        ArrayD::from_shape_vec(IxDyn(&shape), out)
+            .map_err(|err| Error::OtherError(err.to_string()))
+    }};
+}
+
+/// Apply `f` to each cash flow along `axis` of `values`, borrowing it when it is contiguous
+fn along_axis(
+    values: ArrayView2<'_, f64>,
+    axis: Axis,
+    f: impl Fn(&[f64]) -> Result<f64>,
+) -> Result<Array1<f64>> {
+    // `lanes` panics on an axis out of range, where numpy raises `AxisError`
+    if axis.index() >= values.ndim() {
+        return Err(Error::ParaError(format!(
+            "axis: {} is out of bounds for an array of dimension {}",
+            axis.index(),
+            values.ndim()
+        )));
+    }
+    values
+        .lanes(axis)
+        .into_iter()
+        .map(|lane| match lane.as_slice() {
+            Some(values) => f(values),
+            None => f(&lane.to_vec()),
+        })
+        .collect::<Result<Vec<f64>>>()
+        .map(Array1::from)
+}
+
+/// Future value, see [`FutureValue`]
+pub fn fv<D1, D2, D3, D4, D5>(
+    rate: ArrayView<'_, f64, D1>,
+    nper: ArrayView<'_, u32, D2>,
+    pmt: ArrayView<'_, f64, D3>,
+    pv: ArrayView<'_, f64, D4>,
+    when: ArrayView<'_, WhenType, D5>,
+) -> Result<ArrayD<f64>>
+where
+    D1: Dimension,
+    D2: Dimension,
+    D3: Dimension,
+    D4: Dimension,
+    D5: Dimension,
+{
+    elementwise!((rate, nper, pmt, pv, when) => {
+        FutureValue::from_tuple((rate, nper, pmt, pv, when)).get()
+    })
+}
+
+/// Present value, see [`PresentValue`]
+pub fn pv<D1, D2, D3, D4, D5>(
+    rate: ArrayView<'_, f64, D1>,
+    nper: ArrayView<'_, u32, D2>,
+    pmt: ArrayView<'_, f64, D3>,
+    fv: ArrayView<'_, f64, D4>,
+    when: ArrayView<'_, WhenType, D5>,
+) -> Result<ArrayD<f64>>
+where
+    D1: Dimension,
+    D2: Dimension,
+    D3: Dimension,
+    D4: Dimension,
+    D5: Dimension,
+{
+    elementwise!((rate, nper, pmt, fv, when) => {
+        PresentValue::from_tuple((rate, nper, pmt, fv, when)).get()
+    })
+}
+
+/// Payment against loan principal plus interest, see [`Payment`]
+pub fn pmt<D1, D2, D3, D4, D5>(
+    rate: ArrayView<'_, f64, D1>,
+    nper: ArrayView<'_, u32, D2>,
+    pv: ArrayView<'_, f64, D3>,
+    fv: ArrayView<'_, f64, D4>,
+    when: ArrayView<'_, WhenType, D5>,
+) -> Result<ArrayD<f64>>
+where
+    D1: Dimension,
+    D2: Dimension,
+    D3: Dimension,
+    D4: Dimension,
+    D5: Dimension,
+{
+    elementwise!((rate, nper, pv, fv, when) => {
+        Payment::from_tuple((rate, nper, pv, fv, when)).get()
+    })
+}
+
+/// Number of periodic payments, see [`NumberPeriod`]
+pub fn nper<D1, D2, D3, D4, D5>(
+    rate: ArrayView<'_, f64, D1>,
+    pmt: ArrayView<'_, f64, D2>,
+    pv: ArrayView<'_, f64, D3>,
+    fv: ArrayView<'_, f64, D4>,
+    when: ArrayView<'_, WhenType, D5>,
+) -> Result<ArrayD<f64>>
+where
+    D1: Dimension,
+    D2: Dimension,
+    D3: Dimension,
+    D4: Dimension,
+    D5: Dimension,
+{
+    elementwise!((rate, pmt, pv, fv, when) => {
+        let nper = NumberPeriod::from_tuple((rate, pmt, pv, fv, when)).get()?;
+        Ok(nper.unwrap_or(f64::NAN))
+    })
+}
+
+/// Interest portion of payment `per`, see [`InterestPayment`]
+pub fn ipmt<D1, D2, D3, D4, D5, D6>(
+    rate: ArrayView<'_, f64, D1>,
+    per: ArrayView<'_, u32, D2>,
+    nper: ArrayView<'_, u32, D3>,
+    pv: ArrayView<'_, f64, D4>,
+    fv: ArrayView<'_, f64, D5>,
+    when: ArrayView<'_, WhenType, D6>,
+) -> Result<ArrayD<f64>>
+where
+    D1: Dimension,
+    D2: Dimension,
+    D3: Dimension,
+    D4: Dimension,
+    D5: Dimension,
+    D6: Dimension,
+{
+    elementwise!((rate, per, nper, pv, fv, when) => {
+        let ipmt = InterestPayment::from_tuple((rate, per, nper, pv, fv, when)).get()?;
+        Ok(ipmt.unwrap_or(f64::NAN))
+    })
+}
+
+/// Principal portion of payment `per`, see [`PrincipalPayment`]
+pub fn ppmt<D1, D2, D3, D4, D5, D6>(
+    rate: ArrayView<'_, f64, D1>,
+    per: ArrayView<'_, u32, D2>,
+    nper: ArrayView<'_, u32, D3>,
+    pv: ArrayView<'_, f64, D4>,
+    fv: ArrayView<'_, f64, D5>,
+    when: ArrayView<'_, WhenType, D6>,
+) -> Result<ArrayD<f64>>
+where
+    D1: Dimension,
+    D2: Dimension,
+    D3: Dimension,
+    D4: Dimension,
+    D5: Dimension,
+    D6: Dimension,
+{
+    elementwise!((rate, per, nper, pv, fv, when) => {
+        let ppmt = PrincipalPayment::from_tuple((rate, per, nper, pv, fv, when)).get()?;
+        Ok(ppmt.unwrap_or(f64::NAN))
+    })
+}
+
+/// Rate of interest per period, see [`Rate`]. `guess`, `tol` and `maxiter` are shared by all elements
+#[allow(clippy::too_many_arguments)]
+pub fn rate<D1, D2, D3, D4, D5>(
+    nper: ArrayView<'_, u32, D1>,
+    pmt: ArrayView<'_, f64, D2>,
+    pv: ArrayView<'_, f64, D3>,
+    fv: ArrayView<'_, f64, D4>,
+    when: ArrayView<'_, WhenType, D5>,
+    guess: f64,
+    tol: f64,
+    maxiter: u32,
+) -> Result<ArrayD<f64>>
+where
+    D1: Dimension,
+    D2: Dimension,
+    D3: Dimension,
+    D4: Dimension,
+    D5: Dimension,
+{
+    elementwise!((nper, pmt, pv, fv, when) => {
+        let tup = (nper, pmt, pv, fv, when, guess, tol, maxiter);
+        Ok(Rate::from_tuple(tup).get()?.unwrap_or(f64::NAN))
+    })
+}
+
+/// Internal rate of return of each cash flow along `axis`, see [`InternalRateReturn`]
+pub fn irr(values: ArrayView2<'_, f64>, axis: Axis) -> Result<Array1<f64>> {
+    along_axis(values, axis, |values| {
+        let irr = InternalRateReturn::from_slice(values).get()?;
+        Ok(irr.unwrap_or(f64::NAN))
+    })
+}
+
+/// Net present value of each cash flow along `axis`, see [`NetPresentValue`]
+pub fn npv(rate: f64, values: ArrayView2<'_, f64>, axis: Axis) -> Result<Array1<f64>> {
+    along_axis(values, axis, |values| {
+        NetPresentValue::from_slice((values, rate)).get()
+    })
+}
+
+/// Modified internal rate of return of each cash flow along `axis`, see [`ModifiedIRR`]
+pub fn mirr(
+    values: ArrayView2<'_, f64>,
+    axis: Axis,
+    finance_rate: f64,
+    reinvest_rate: f64,
+) -> Result<Array1<f64>> {
+    along_axis(values, axis, |values| {
+        let mirr = ModifiedIRR::from_slice((values, finance_rate, reinvest_rate)).get()?;
+        Ok(mirr.unwrap_or(f64::NAN))
+    })
+}
+
+#[allow(unused_imports)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use ndarray::{array, aview0, aview1, Ix2};
+
+    #[test]
+    fn test_array_broadcast() {
+        // npf.pmt([[0.04], [0.05], [0.06]], [180, 360], 250000) / 12 on the rates
+        let rates = array![[0.04], [0.05], [0.06]] / 12.0;
+        let nper = array![180, 360];
+        let end = aview0(&WhenType::End);
+        let res = pmt(
+            rates.view(),
+            nper.view(),
+            aview0(&250000.0),
+            aview0(&0.0),
+            end,
+        )
+        .unwrap();
+        assert_eq!(res.shape(), &[3, 2]);
+        let res = res.into_dimensionality::<Ix2>().unwrap();
+        for ((i, j), &r) in res.indexed_iter() {
+            let tup = (rates[[i, 0]], nper[[j]], 250000.0, 0.0, WhenType::End);
+            let tgt = Payment::from_tuple(tup).get().unwrap();
+            assert_eq!(r, tgt);
+        }
+
+        // npf.fv(0.075, 20, -2000, 0, ['end', 'begin'])
+        let when = aview1(&[WhenType::End, WhenType::Begin]);
+        let res = fv(
+            aview0(&0.075),
+            aview0(&20),
+            aview0(&-2000.0),
+            aview0(&0.0),
+            when,
+        )
+        .unwrap();
+        let tgt = [86609.36267304, 93105.06487352126];
+        for (res, tgt) in res.iter().zip(tgt) {
+            assert!(float_close(*res, tgt, RTOL, ATOL), "{} v.s. {}", res, tgt);
+        }
+
+        // scalars only give a 0-d array
+        let res = pv(
+            aview0(&0.05),
+            aview0(&10),
+            aview0(&-100.0),
+            aview0(&0.0),
+            end,
+        )
+        .unwrap();
+        assert_eq!(res.ndim(), 0);
+    }
+
+    #[test]
+    fn test_array_no_solution() {
+        // ipmt of the 0th period has no solution
+        let per = array![0, 1];
+        let end = aview0(&WhenType::End);
+        let res = ipmt(
+            aview0(&0.01),
+            per.view(),
+            aview0(&24),
+            aview0(&2000.0),
+            aview0(&0.0),
+            end,
+        )
+        .unwrap();
+        assert!(res[[0]].is_nan());
+        assert!(float_close(res[[1]], -20.0, RTOL, ATOL), "{}", res[[1]]);
+
+        // shapes (2,) and (3,) do not broadcast
+        let res = nper(
+            aview1(&[0.01, 0.02]),
+            aview1(&[-100.0, -200.0, -300.0]),
+            aview0(&1000.0),
+            aview0(&0.0),
+            end,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_array_along_axis() {
+        let values = array![
+            [-150000.0, 15000.0, 25000.0, 35000.0, 45000.0, 60000.0],
+            [-100.0, 39.0, 59.0, 55.0, 20.0, 0.0]
+        ];
+        let rows = npv(0.05, values.view(), Axis(1)).unwrap();
+        // a cash flow in each column, which is not contiguous
+        let transposed = values.t().to_owned();
+        let cols = npv(0.05, transposed.view(), Axis(0)).unwrap();
+        assert_eq!(rows, cols);
+        for (row, res) in values.rows().into_iter().zip(rows) {
+            let tgt = NetPresentValue::from_tuple((row.to_vec(), 0.05))
+                .get()
+                .unwrap();
+            assert_eq!(res, tgt);
+        }
+
+        // npf.irr([-150000, 15000, 25000, 35000, 45000, 60000])
+        let res = irr(values.view(), Axis(1)).unwrap();
+        let tgt = 0.052432888859413884;
+        assert!(
+            float_close(res[0], tgt, RTOL, ATOL),
+            "{} v.s. {}",
+            res[0],
+            tgt
+        );
+
+        let res = mirr(transposed.view(), Axis(0), 0.1, 0.12).unwrap();
+        assert_eq!(res.len(), 2);
+        let positive = array![[100.0, 200.0]];
+        let res = mirr(positive.view(), Axis(1), 0.1, 0.12).unwrap();
+        assert!(res[0].is_nan());
+
+        assert!(npv(0.1, values.view(), Axis(2)).is_err());
+        assert!(irr(values.view(), Axis(2)).is_err());
+    }
+}
@@ -0,0 +1,450 @@
+#[cfg(feature = "std")]
+use crate::{get_f64, get_vecf64, Error, ParaMap};
+use crate::{NetPresentValue, Payment, Result, WhenType};
+use alloc::vec::Vec;
+
+/// # Compute the profitability index of a cash flow, given an interest rate
+/// This is the present value of the cash flows after the initial investment over the initial investment,
+/// i.e. `1 + npv / -values[0]`
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period
+/// * `rate` : an interest rate compounded once per period
+///
+/// ## Return:
+/// * `pi`: the profitability index or `None` if the first value is not an investment, i.e. not negative
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let pi = ProfitabilityIndex::from_tuple((vec![-1000.0, 300.0, 400.0, 500.0], 0.05));
+/// println!("{:#?}'s pi is {:?}", pi, pi.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfitabilityIndex {
+    values: Vec<f64>,
+    rate: f64,
+}
+
+/// # Compute the benefit-cost ratio of a cash flow, given an interest rate
+/// This is the present value of all inflows over the present value of all outflows
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period
+/// * `rate` : an interest rate compounded once per period
+///
+/// ## Return:
+/// * `bcr`: the benefit-cost ratio or `None` if there is no outflow
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let bcr = BenefitCostRatio::from_tuple((vec![-1000.0, 300.0, -100.0, 500.0, 600.0], 0.05));
+/// println!("{:#?}'s bcr is {:?}", bcr, bcr.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BenefitCostRatio {
+    values: Vec<f64>,
+    rate: f64,
+}
+
+/// # Compute the equivalent annual annuity of a cash flow, given an interest rate
+/// This is the level payment over the life of the project that has the same net present value, which makes projects
+/// of unequal lives comparable
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period,
+///   so the life of the project is `values.len() - 1` periods
+/// * `rate` : an interest rate compounded once per period
+///
+/// ## Return:
+/// * `eaa`: the equivalent annual annuity or `None` if the project has no life after `t=0`
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let eaa = EquivalentAnnualAnnuity::from_tuple((vec![-1000.0, 300.0, 400.0, 500.0], 0.05));
+/// println!("{:#?}'s eaa is {:?}", eaa, eaa.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EquivalentAnnualAnnuity {
+    values: Vec<f64>,
+    rate: f64,
+}
+
+/// # Compute the equivalent annual cost of a cash flow, given an interest rate
+/// This is the level cost over the life of an asset that has the same net present value, i.e. the negative of
+/// [`EquivalentAnnualAnnuity`], so costs given as negative values produce a positive cost
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period,
+///   so the life of the asset is `values.len() - 1` periods
+/// * `rate` : an interest rate compounded once per period
+///
+/// ## Return:
+/// * `eac`: the equivalent annual cost or `None` if the asset has no life after `t=0`
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let eac = EquivalentAnnualCost::from_tuple((vec![-5000.0, -200.0, -200.0, -200.0, 500.0], 0.05));
+/// println!("{:#?}'s eac is {:?}", eac, eac.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EquivalentAnnualCost {
+    values: Vec<f64>,
+    rate: f64,
+}
+
+fn npv(values: &[f64], rate: f64) -> Result<f64> {
+    NetPresentValue::from_slice((values, rate)).get()
+}
+
+fn eaa(values: &[f64], rate: f64) -> Result<Option<f64>> {
+    if values.len() <= 1 {
+        return Ok(None);
+    }
+    let life = values.len() as u32 - 1;
+    let npv = npv(values, rate)?;
+    let pmt = Payment::from_tuple((rate, life, npv, 0.0, WhenType::End)).get()?;
+    Ok(Some(-pmt))
+}
+
+impl ProfitabilityIndex {
+    /// Instantiate a `ProfitabilityIndex` instance from a tuple of (`values`, `rate`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, f64)) -> Self {
+        ProfitabilityIndex {
+            values: tup.0,
+            rate: tup.1,
+        }
+    }
+
+    /// Instantiate a `ProfitabilityIndex` instance from a hash map with keys of (`values`, `rate`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `ProfitabilityIndex` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        let rate = get_f64(&map, "rate").map_err(op)?;
+        Ok(ProfitabilityIndex { values, rate })
+    }
+
+    fn pi(&self) -> Result<Option<f64>> {
+        match self.values.first() {
+            Some(&investment) if investment < 0.0 => {
+                let npv = npv(&self.values, self.rate)?;
+                Ok(Some(1.0 + npv / -investment))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Get the profitability index from an instance of `ProfitabilityIndex`
+    pub fn get(&self) -> Result<Option<f64>> {
+        self.pi()
+    }
+}
+
+impl BenefitCostRatio {
+    /// Instantiate a `BenefitCostRatio` instance from a tuple of (`values`, `rate`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, f64)) -> Self {
+        BenefitCostRatio {
+            values: tup.0,
+            rate: tup.1,
+        }
+    }
+
+    /// Instantiate a `BenefitCostRatio` instance from a hash map with keys of (`values`, `rate`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `BenefitCostRatio` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        let rate = get_f64(&map, "rate").map_err(op)?;
+        Ok(BenefitCostRatio { values, rate })
+    }
+
+    fn bcr(&self) -> Result<Option<f64>> {
+        let benefits: Vec<f64> = self.values.iter().map(|&v| v.max(0.0)).collect();
+        let costs: Vec<f64> = self.values.iter().map(|&v| v.min(0.0)).collect();
+        let costs = npv(&costs, self.rate)?;
+        if costs == 0.0 {
+            return Ok(None);
+        }
+        let benefits = npv(&benefits, self.rate)?;
+        Ok(Some(benefits / -costs))
+    }
+
+    /// Get the benefit-cost ratio from an instance of `BenefitCostRatio`
+    pub fn get(&self) -> Result<Option<f64>> {
+        self.bcr()
+    }
+}
+
+impl EquivalentAnnualAnnuity {
+    /// Instantiate an `EquivalentAnnualAnnuity` instance from a tuple of (`values`, `rate`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, f64)) -> Self {
+        EquivalentAnnualAnnuity {
+            values: tup.0,
+            rate: tup.1,
+        }
+    }
+
+    /// Instantiate an `EquivalentAnnualAnnuity` instance from a hash map with keys of (`values`, `rate`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `EquivalentAnnualAnnuity` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        let rate = get_f64(&map, "rate").map_err(op)?;
+        Ok(EquivalentAnnualAnnuity { values, rate })
+    }
+
+    /// Get the equivalent annual annuity from an instance of `EquivalentAnnualAnnuity`
+    pub fn get(&self) -> Result<Option<f64>> {
+        eaa(&self.values, self.rate)
+    }
+}
+
+impl EquivalentAnnualCost {
+    /// Instantiate an `EquivalentAnnualCost` instance from a tuple of (`values`, `rate`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, f64)) -> Self {
+        EquivalentAnnualCost {
+            values: tup.0,
+            rate: tup.1,
+        }
+    }
+
+    /// Instantiate an `EquivalentAnnualCost` instance from a hash map with keys of (`values`, `rate`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `EquivalentAnnualCost` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        let rate = get_f64(&map, "rate").map_err(op)?;
+        Ok(EquivalentAnnualCost { values, rate })
+    }
+
+    /// Get the equivalent annual cost from an instance of `EquivalentAnnualCost`
+    pub fn get(&self) -> Result<Option<f64>> {
+        Ok(eaa(&self.values, self.rate)?.map(|eaa| -eaa))
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_pi_from_tuple() {
+        // npf.npv(0.1, [-1000, 400, 500, 600])
+        // 227.64838467317804
+        let pi = ProfitabilityIndex::from_tuple((vec![-1000.0, 400.0, 500.0, 600.0], 0.1));
+        let res = pi.get().unwrap().unwrap();
+        let tgt = 1.227648384673178;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_pi_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 400.0, 500.0, 600.0]),
+        );
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        let pi = ProfitabilityIndex::from_map(map).unwrap();
+        let res = pi.get().unwrap().unwrap();
+        let tgt = 1.227648384673178;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_pi_no_investment() {
+        let pi = ProfitabilityIndex::from_tuple((vec![1000.0, 400.0], 0.1));
+        let res = pi.get().unwrap();
+        let tgt = None;
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_bcr_from_tuple() {
+        let bcr = BenefitCostRatio::from_tuple((vec![-1000.0, 300.0, -100.0, 500.0, 600.0], 0.1));
+        let res = bcr.get().unwrap().unwrap();
+        let tgt = (300.0 / 1.1 + 500.0 / 1.1f64.powi(3) + 600.0 / 1.1f64.powi(4))
+            / (1000.0 + 100.0 / 1.1f64.powi(2));
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_bcr_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 400.0, 500.0, 600.0]),
+        );
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        // with a single outflow at present it is the same as the profitability index
+        let res = BenefitCostRatio::from_map(map)
+            .unwrap()
+            .get()
+            .unwrap()
+            .unwrap();
+        let tgt = 1.227648384673178;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_bcr_no_cost() {
+        let bcr = BenefitCostRatio::from_tuple((vec![100.0, 400.0], 0.1));
+        let res = bcr.get().unwrap();
+        let tgt = None;
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_eaa_from_tuple() {
+        // npv = 227.64838467317804 spread over 3 periods
+        // -npf.pmt(0.1, 3, 227.64838467317804)
+        // 91.54078549848934
+        let eaa = EquivalentAnnualAnnuity::from_tuple((vec![-1000.0, 400.0, 500.0, 600.0], 0.1));
+        let res = eaa.get().unwrap().unwrap();
+        let tgt = 91.54078549848934;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_eaa_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 400.0, 500.0, 600.0]),
+        );
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        let res = EquivalentAnnualAnnuity::from_map(map)
+            .unwrap()
+            .get()
+            .unwrap()
+            .unwrap();
+        let tgt = 91.54078549848934;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_eaa_no_life() {
+        let eaa = EquivalentAnnualAnnuity::from_tuple((vec![-1000.0], 0.1));
+        let res = eaa.get().unwrap();
+        let tgt = None;
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_eac_from_tuple() {
+        // a machine costing 1000 with no running cost and no salvage over 2 periods
+        // npf.pmt(0.1, 2, -1000)
+        // 576.1904761904758
+        let eac = EquivalentAnnualCost::from_tuple((vec![-1000.0, 0.0, 0.0], 0.1));
+        let res = eac.get().unwrap().unwrap();
+        let tgt = 576.1904761904758;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_eac_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 0.0, 0.0]),
+        );
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        let res = EquivalentAnnualCost::from_map(map)
+            .unwrap()
+            .get()
+            .unwrap()
+            .unwrap();
+        let tgt = 576.1904761904758;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_budget_err() {
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(vec![-1000.0, 400.0]));
+        assert!(ProfitabilityIndex::from_map(map).is_err());
+
+        let mut map = ParaMap::new();
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        assert!(BenefitCostRatio::from_map(map).is_err());
+
+        let mut map = ParaMap::new();
+        map.insert("Values".to_string(), ParaType::VecF64(vec![-1000.0, 400.0]));
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        assert!(EquivalentAnnualAnnuity::from_map(map).is_err());
+
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(vec![-1000.0, 400.0]));
+        map.insert("Rate".to_string(), ParaType::F64(0.1));
+        assert!(EquivalentAnnualCost::from_map(map).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use crate::*;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// # Common interface of all calculators
+/// It ties together how a calculator is built, what it computes and which parameters it takes, so generic code such
+/// as batch evaluation, caching or logging can be written once for all of them
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// // evaluate any calculator over many inputs
+/// fn log<C: Calculator>(inputs: &[C::Input]) -> Vec<Result<C::Output>>
+/// where
+///     C::Input: Clone + Sync,
+///     C::Output: Send + std::fmt::Debug,
+/// {
+///     let outputs = C::batch(inputs);
+///     println!("{} is {:?} of {:?}", C::NAME, outputs, C::parameters());
+///     outputs
+/// }
+/// let pmts = log::<Payment>(&[
+///     (0.08 / 12.0, 60, 15000.0, 0.0, WhenType::End),
+///     (0.06 / 12.0, 360, 250000.0, 0.0, WhenType::End),
+/// ]);
+/// ```
+pub trait Calculator: Sized {
+    /// the tuple taken by `from_tuple`
+    type Input;
+    /// the type returned by `get`
+    type Output;
+    /// the name the calculator is registered under, see [`evaluate`]
+    const NAME: &'static str;
+
+    /// Instantiate a calculator from its tuple of inputs
+    fn from_input(input: Self::Input) -> Self;
+
+    /// Instantiate a calculator from a hash map of its parameters
+    fn from_params(map: ParaMap) -> Result<Self>;
+
+    /// Compute the output of the calculator
+    fn compute(&self) -> Result<Self::Output>;
+
+    /// Get the parameters of the calculator, in the order of its `Input`
+    fn parameters() -> Vec<ParaSpec> {
+        // every calculator is registered, see `calculator::tests::test_calculator_parameters`
+        parameters(Self::NAME).unwrap_or_default()
+    }
+
+    /// Compute the output for each of `inputs`, in parallel with the `rayon` feature
+    /// Outputs are in the order of `inputs`, and a row that fails does not stop the others
+    fn batch(inputs: &[Self::Input]) -> Vec<Result<Self::Output>>
+    where
+        Self::Input: Clone + Sync,
+        Self::Output: Send,
+    {
+        map_rows(inputs, |input| Self::from_input(input.clone()).compute())
+    }
+
+    /// Compute the output for each hash map of parameters, in parallel with the `rayon` feature, see [`Self::batch`]
+    fn batch_params(maps: &[ParaMap]) -> Vec<Result<Self::Output>>
+    where
+        Self::Output: Send,
+    {
+        map_rows(maps, |map| Self::from_params(map.clone())?.compute())
+    }
+}
+
+// apply `f` to each row, in parallel with the `rayon` feature, keeping the order of `rows`
+#[cfg(feature = "rayon")]
+fn map_rows<T: Sync, U: Send>(rows: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    rows.par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn map_rows<T, U>(rows: &[T], f: impl Fn(&T) -> U) -> Vec<U> {
+    rows.iter().map(f).collect()
+}
+
+macro_rules! impl_calculator {
+    ($t:ty, $name:literal, $input:ty, $output:ty) => {
+        impl Calculator for $t {
+            type Input = $input;
+            type Output = $output;
+            const NAME: &'static str = $name;
+
+            fn from_input(input: Self::Input) -> Self {
+                <$t>::from_tuple(input)
+            }
+
+            fn from_params(map: ParaMap) -> Result<Self> {
+                <$t>::from_map(map)
+            }
+
+            fn compute(&self) -> Result<Self::Output> {
+                self.get()
+            }
+        }
+    };
+}
+
+impl_calculator!(FutureValue, "fv", (f64, u32, f64, f64, WhenType), f64);
+impl_calculator!(PresentValue, "pv", (f64, u32, f64, f64, WhenType), f64);
+impl_calculator!(Payment, "pmt", (f64, u32, f64, f64, WhenType), f64);
+impl_calculator!(
+    NumberPeriod,
+    "nper",
+    (f64, f64, f64, f64, WhenType),
+    Option<f64>
+);
+impl_calculator!(
+    InterestPayment,
+    "ipmt",
+    (f64, u32, u32, f64, f64, WhenType),
+    Option<f64>
+);
+impl_calculator!(
+    PrincipalPayment,
+    "ppmt",
+    (f64, u32, u32, f64, f64, WhenType),
+    Option<f64>
+);
+impl_calculator!(
+    Rate,
+    "rate",
+    (u32, f64, f64, f64, WhenType, f64, f64, u32),
+    Option<f64>
+);
+impl_calculator!(NetPresentValue<'_>, "npv", (Vec<f64>, f64), f64);
+impl_calculator!(ModifiedIRR<'_>, "mirr", (Vec<f64>, f64, f64), Option<f64>);
+impl_calculator!(
+    AdjustableRateLoan,
+    "arm",
+    (
+        f64,
+        u32,
+        f64,
+        f64,
+        WhenType,
+        u32,
+        u32,
+        Vec<f64>,
+        f64,
+        f64,
+        f64,
+        f64
+    ),
+    Vec<AmortizationRow>
+);
+impl_calculator!(
+    FutureValueSchedule,
+    "fvschedule",
+    (Vec<f64>, f64, f64, WhenType),
+    f64
+);
+impl_calculator!(
+    PresentValueSchedule,
+    "pvschedule",
+    (Vec<f64>, f64, f64, WhenType),
+    f64
+);
+impl_calculator!(
+    NetPresentValueSchedule,
+    "npvschedule",
+    (Vec<f64>, Vec<f64>),
+    f64
+);
+impl_calculator!(
+    FutureValueStream,
+    "fvstream",
+    (f64, Vec<f64>, f64, WhenType),
+    f64
+);
+impl_calculator!(
+    DiscountedPaybackPeriod,
+    "discounted_payback",
+    (Vec<f64>, f64),
+    Option<f64>
+);
+impl_calculator!(ProfitabilityIndex, "pi", (Vec<f64>, f64), Option<f64>);
+impl_calculator!(BenefitCostRatio, "bcr", (Vec<f64>, f64), Option<f64>);
+impl_calculator!(EquivalentAnnualAnnuity, "eaa", (Vec<f64>, f64), Option<f64>);
+impl_calculator!(EquivalentAnnualCost, "eac", (Vec<f64>, f64), Option<f64>);
+
+// calculators built from a vector rather than a tuple
+impl Calculator for InternalRateReturn<'_> {
+    type Input = Vec<f64>;
+    type Output = Option<f64>;
+    const NAME: &'static str = "irr";
+
+    fn from_input(input: Self::Input) -> Self {
+        InternalRateReturn::from_vec(input)
+    }
+
+    fn from_params(map: ParaMap) -> Result<Self> {
+        InternalRateReturn::from_map(map)
+    }
+
+    fn compute(&self) -> Result<Self::Output> {
+        self.get()
+    }
+}
+
+impl Calculator for PaybackPeriod {
+    type Input = Vec<f64>;
+    type Output = Option<f64>;
+    const NAME: &'static str = "payback";
+
+    fn from_input(input: Self::Input) -> Self {
+        PaybackPeriod::from_vec(input)
+    }
+
+    fn from_params(map: ParaMap) -> Result<Self> {
+        PaybackPeriod::from_map(map)
+    }
+
+    fn compute(&self) -> Result<Self::Output> {
+        self.get()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    fn eval<C: Calculator>(input: C::Input) -> Result<C::Output> {
+        C::from_input(input).compute()
+    }
+
+    #[test]
+    fn test_calculator_compute() {
+        // res = npf.pmt(0.08 / 12, 5 * 12, 15000)
+        // tgt = -304.145914
+        let res = eval::<Payment>((0.08 / 12.0, 60, 15000.0, 0.0, WhenType::End)).unwrap();
+        let tgt = -304.145914;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        // npf.irr([-150000, 15000, 25000, 35000, 45000, 60000])
+        // 0.052432888859413884
+        let values = vec![-150000.0, 15000.0, 25000.0, 35000.0, 45000.0, 60000.0];
+        let res = eval::<InternalRateReturn>(values).unwrap().unwrap();
+        let tgt = 0.052432888859413884;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_calculator_from_params() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 500.0, 500.0]),
+        );
+        let res = PaybackPeriod::from_params(map).unwrap().compute().unwrap();
+        assert_eq!(res, Some(2.0));
+    }
+
+    #[test]
+    fn test_calculator_batch() {
+        let inputs: Vec<_> = (1..=1000)
+            .map(|n| (0.05 / 12.0, n, 250000.0, 0.0, WhenType::End))
+            .collect();
+        let res = Payment::batch(&inputs);
+        assert_eq!(res.len(), inputs.len());
+        for (input, res) in inputs.into_iter().zip(res) {
+            assert_eq!(res.unwrap(), eval::<Payment>(input).unwrap());
+        }
+
+        // interest and principal of each period add up to the payment
+        let tups: Vec<_> = (1..=12)
+            .map(|per| (0.1 / 12.0, per, 12, 1000.0, 0.0, WhenType::End))
+            .collect();
+        let ipmts = InterestPayment::batch(&tups);
+        let ppmts = PrincipalPayment::batch(&tups);
+        let tgt = eval::<Payment>((0.1 / 12.0, 12, 1000.0, 0.0, WhenType::End)).unwrap();
+        for (ipmt, ppmt) in ipmts.into_iter().zip(ppmts) {
+            let res = ipmt.unwrap().unwrap() + ppmt.unwrap().unwrap();
+            assert!(float_close(res, tgt, RTOL, ATOL), "{} v.s. {}", res, tgt);
+        }
+    }
+
+    #[test]
+    fn test_calculator_batch_params() {
+        let mut map = ParaMap::new();
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        map.insert("nper".to_string(), ParaType::U32(12));
+        map.insert("pv".to_string(), ParaType::F64(1000.0));
+        map.insert("fv".to_string(), ParaType::F64(0.0));
+        map.insert("when".to_string(), ParaType::When(WhenType::End));
+        let mut bad = map.clone();
+        bad.remove("pv");
+
+        // the row missing `pv` fails on its own, in its place
+        let res = Payment::batch_params(&[map.clone(), bad, map]);
+        assert!(res[0].is_ok());
+        assert!(res[1].is_err());
+        assert_eq!(res[2].as_ref().unwrap(), res[0].as_ref().unwrap());
+    }
+
+    #[test]
+    fn test_calculator_parameters() {
+        assert_eq!(FutureValue::NAME, "fv");
+        let names: Vec<&str> = Rate::parameters().iter().map(|spec| spec.name).collect();
+        assert_eq!(
+            names,
+            vec!["nper", "pmt", "pv", "fv", "when", "guess", "tol", "maxiter"]
+        );
+        assert_eq!(AdjustableRateLoan::parameters().len(), 12);
+    }
+
+    #[test]
+    fn test_calculator_default() {
+        let rate = Rate::default();
+        assert_eq!(
+            rate,
+            Rate::from_tuple((0, 0.0, 0.0, 0.0, WhenType::End, 0.1, 1e-6, 100))
+        );
+        assert_eq!(
+            FutureValue::default(),
+            FutureValue::from_tuple((0.0, 0, 0.0, 0.0, WhenType::End))
+        );
+    }
+}
@@ -0,0 +1,605 @@
+//! # C ABI
+//! `extern "C"` functions for C and C++, built with the `capi` feature. The header is `include/rfinancial.h`,
+//! generated by [`header`].
+//!
+//! Conventions of every function:
+//! * the return value is a status code, `RF_OK` on success and otherwise one mapped from [`Error`]
+//! * a panic does not unwind across the ABI, it is caught and `RF_PANIC` is returned
+//! * results are written through out-parameters, which must not be null
+//! * results that may have no solution, i.e. an `Option` in Rust, have an extra `bool *has_value`;
+//!   without a solution it is `false` and the result is `NAN`
+//! * cash flows and rates are a pointer and a length, the pointer may be null only if the length is `0`
+//! * `when` is `0` for the end and `1` for the beginning of periods
+//!
+//! ## Example
+//! ```c
+//! #include "rfinancial.h"
+//! double pmt;
+//! if (rf_pmt(0.075 / 12, 12 * 15, 200000, 0, 0, &pmt) == RF_OK) {
+//!     printf("pmt is %f\n", pmt);
+//! }
+//! ```
+use crate::{
+    AdjustableRateLoan, BenefitCostRatio, DiscountedPaybackPeriod, EquivalentAnnualAnnuity,
+    EquivalentAnnualCost, Error, FutureValue, FutureValueSchedule, FutureValueStream,
+    InterestPayment, InternalRateReturn, ModifiedIRR, NetPresentValue, NetPresentValueSchedule,
+    NumberPeriod, PaybackPeriod, Payment, PresentValue, PresentValueSchedule, PrincipalPayment,
+    ProfitabilityIndex, Rate, WhenType,
+};
+
+/// Status code returned by every function
+pub type Status = i32;
+
+/// Success
+pub const RF_OK: Status = 0;
+/// [`Error::ParaError`], an invalid parameter
+pub const RF_PARA_ERROR: Status = 1;
+/// [`Error::ConstructorError`], an instance cannot be constructed
+pub const RF_CONSTRUCTOR_ERROR: Status = 2;
+/// [`Error::OtherError`]
+pub const RF_OTHER_ERROR: Status = 3;
+/// A null pointer where a value is required
+pub const RF_NULL_POINTER: Status = 4;
+/// An output buffer too small for the result
+pub const RF_BUFFER_TOO_SMALL: Status = 5;
+/// A panic caught before it unwinds into the caller
+pub const RF_PANIC: Status = 6;
+
+const STATUSES: [(&str, Status, &str); 7] = [
+    ("RF_OK", RF_OK, "Success"),
+    ("RF_PARA_ERROR", RF_PARA_ERROR, "An invalid parameter"),
+    (
+        "RF_CONSTRUCTOR_ERROR",
+        RF_CONSTRUCTOR_ERROR,
+        "An instance cannot be constructed",
+    ),
+    ("RF_OTHER_ERROR", RF_OTHER_ERROR, "Any other error"),
+    (
+        "RF_NULL_POINTER",
+        RF_NULL_POINTER,
+        "A null pointer where a value is required",
+    ),
+    (
+        "RF_BUFFER_TOO_SMALL",
+        RF_BUFFER_TOO_SMALL,
+        "An output buffer too small for the result",
+    ),
+    (
+        "RF_PANIC",
+        RF_PANIC,
+        "A panic caught before it unwinds into the caller",
+    ),
+];
+
+/// One period of an amortization schedule, see [`crate::AmortizationRow`]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CAmortizationRow {
+    pub per: u32,
+    pub rate: f64,
+    pub pmt: f64,
+    pub ipmt: f64,
+    pub ppmt: f64,
+    pub balance: f64,
+}
+
+const ROW: &str = "\
+/// One period of an amortization schedule
+typedef struct rf_amortization_row {
+    uint32_t per;
+    double rate;
+    double pmt;
+    double ipmt;
+    double ppmt;
+    double balance;
+} rf_amortization_row;
+";
+
+fn status(err: Error) -> Status {
+    match err {
+        Error::ParaError(_) => RF_PARA_ERROR,
+        Error::ConstructorError(_) => RF_CONSTRUCTOR_ERROR,
+        Error::OtherError(_) => RF_OTHER_ERROR,
+    }
+}
+
+fn when_type(when: i32) -> Result<WhenType, Status> {
+    match when {
+        0 => Ok(WhenType::End),
+        1 => Ok(WhenType::Begin),
+        _ => Err(RF_PARA_ERROR),
+    }
+}
+
+unsafe fn values(ptr: *const f64, len: usize) -> Result<Vec<f64>, Status> {
+    slice(ptr, len).map(<[f64]>::to_vec)
+}
+
+/// Borrow the caller's array for the duration of a call
+unsafe fn slice<'a>(ptr: *const f64, len: usize) -> Result<&'a [f64], Status> {
+    match (ptr.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(RF_NULL_POINTER),
+        (false, _) => Ok(std::slice::from_raw_parts(ptr, len)),
+    }
+}
+
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), Status> {
+    if out.is_null() {
+        return Err(RF_NULL_POINTER);
+    }
+    *out = value;
+    Ok(())
+}
+
+/// Write both out-parameters of an optional result, or neither if one of them is null
+unsafe fn write_option(
+    out: *mut f64,
+    has_value: *mut bool,
+    value: Option<f64>,
+) -> Result<(), Status> {
+    if out.is_null() || has_value.is_null() {
+        return Err(RF_NULL_POINTER);
+    }
+    write(has_value, value.is_some())?;
+    write(out, value.unwrap_or(f64::NAN))
+}
+
+/// C type of a Rust parameter type
+macro_rules! c_type {
+    (f64) => {
+        "double "
+    };
+    (u32) => {
+        "uint32_t "
+    };
+    (i32) => {
+        "int32_t "
+    };
+    (usize) => {
+        "size_t "
+    };
+    (*const f64) => {
+        "const double *"
+    };
+    (*mut f64) => {
+        "double *"
+    };
+    (*mut bool) => {
+        "bool *"
+    };
+    (*mut usize) => {
+        "size_t *"
+    };
+    (*mut CAmortizationRow) => {
+        "rf_amortization_row *"
+    };
+}
+
+/// C parameter list of a function
+macro_rules! c_args {
+    () => { "void" };
+    ($arg:ident : $(*$q:ident)? $t:ident) => {
+        concat!(c_type!($(*$q)? $t), stringify!($arg))
+    };
+    ($arg:ident : $(*$q:ident)? $t:ident, $($rest:tt)+) => {
+        concat!(c_type!($(*$q)? $t), stringify!($arg), ", ", c_args!($($rest)+))
+    };
+}
+
+/// Define `extern "C"` functions returning a [`Status`], together with their C prototypes in `PROTOTYPES`
+macro_rules! capi {
+    ($(
+        $(#[doc = $doc:literal])*
+        fn $name:ident($($arg:ident : $(*$q:ident)? $t:ident),* $(,)?) $body:block
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            ///
+            /// # Safety
+            /// Pointers must be valid for their lengths, or for a single value otherwise
+            #[no_mangle]
+            pub unsafe extern "C" fn $name($($arg: $(*$q)? $t),*) -> Status {
+                // unwinding out of an `extern "C"` function aborts the caller
+                let res: std::thread::Result<core::result::Result<(), Status>> =
+                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| $body));
+                match res {
+                    Ok(Ok(())) => RF_OK,
+                    Ok(Err(status)) => status,
+                    Err(_) => RF_PANIC,
+                }
+            }
+        )*
+
+        const PROTOTYPES: &[&str] = &[$(
+            concat!(
+                $("///", $doc, "\n",)*
+                "rf_status ", stringify!($name), "(", c_args!($($arg : $(*$q)? $t),*), ");\n"
+            )
+        ),*];
+    };
+}
+
+capi! {
+    /// Future value, see `FutureValue`
+    fn rf_fv(rate: f64, nper: u32, pmt: f64, pv: f64, when: i32, out: *mut f64) {
+        let tup = (rate, nper, pmt, pv, when_type(when)?);
+        write(out, FutureValue::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Present value, see `PresentValue`
+    fn rf_pv(rate: f64, nper: u32, pmt: f64, fv: f64, when: i32, out: *mut f64) {
+        let tup = (rate, nper, pmt, fv, when_type(when)?);
+        write(out, PresentValue::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Payment against loan principal plus interest, see `Payment`
+    fn rf_pmt(rate: f64, nper: u32, pv: f64, fv: f64, when: i32, out: *mut f64) {
+        let tup = (rate, nper, pv, fv, when_type(when)?);
+        write(out, Payment::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Number of periodic payments, see `NumberPeriod`
+    fn rf_nper(rate: f64, pmt: f64, pv: f64, fv: f64, when: i32, out: *mut f64, has_value: *mut bool) {
+        let tup = (rate, pmt, pv, fv, when_type(when)?);
+        write_option(out, has_value, NumberPeriod::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Interest portion of payment `per`, see `InterestPayment`
+    fn rf_ipmt(
+        rate: f64,
+        per: u32,
+        nper: u32,
+        pv: f64,
+        fv: f64,
+        when: i32,
+        out: *mut f64,
+        has_value: *mut bool,
+    ) {
+        let tup = (rate, per, nper, pv, fv, when_type(when)?);
+        write_option(out, has_value, InterestPayment::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Principal portion of payment `per`, see `PrincipalPayment`
+    fn rf_ppmt(
+        rate: f64,
+        per: u32,
+        nper: u32,
+        pv: f64,
+        fv: f64,
+        when: i32,
+        out: *mut f64,
+        has_value: *mut bool,
+    ) {
+        let tup = (rate, per, nper, pv, fv, when_type(when)?);
+        write_option(out, has_value, PrincipalPayment::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Rate of interest per period, see `Rate`
+    fn rf_rate(
+        nper: u32,
+        pmt: f64,
+        pv: f64,
+        fv: f64,
+        when: i32,
+        guess: f64,
+        tol: f64,
+        maxiter: u32,
+        out: *mut f64,
+        has_value: *mut bool,
+    ) {
+        let tup = (nper, pmt, pv, fv, when_type(when)?, guess, tol, maxiter);
+        write_option(out, has_value, Rate::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Internal rate of return, see `InternalRateReturn`
+    fn rf_irr(values: *const f64, len: usize, out: *mut f64, has_value: *mut bool) {
+        let values = slice(values, len)?;
+        write_option(out, has_value, InternalRateReturn::from_slice(values).get().map_err(status)?)
+    }
+
+    /// Net present value, see `NetPresentValue`
+    fn rf_npv(values: *const f64, len: usize, rate: f64, out: *mut f64) {
+        let tup = (slice(values, len)?, rate);
+        write(out, NetPresentValue::from_slice(tup).get().map_err(status)?)
+    }
+
+    /// Modified internal rate of return, see `ModifiedIRR`
+    fn rf_mirr(
+        values: *const f64,
+        len: usize,
+        finance_rate: f64,
+        reinvest_rate: f64,
+        out: *mut f64,
+        has_value: *mut bool,
+    ) {
+        let tup = (slice(values, len)?, finance_rate, reinvest_rate);
+        write_option(out, has_value, ModifiedIRR::from_slice(tup).get().map_err(status)?)
+    }
+
+    /// Future value with a rate for each period, see `FutureValueSchedule`
+    fn rf_fvschedule(rates: *const f64, len: usize, pmt: f64, pv: f64, when: i32, out: *mut f64) {
+        let tup = (values(rates, len)?, pmt, pv, when_type(when)?);
+        write(out, FutureValueSchedule::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Present value with a rate for each period, see `PresentValueSchedule`
+    fn rf_pvschedule(rates: *const f64, len: usize, pmt: f64, fv: f64, when: i32, out: *mut f64) {
+        let tup = (values(rates, len)?, pmt, fv, when_type(when)?);
+        write(out, PresentValueSchedule::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Net present value with a rate for each period, see `NetPresentValueSchedule`
+    fn rf_npvschedule(
+        values: *const f64,
+        values_len: usize,
+        rates: *const f64,
+        rates_len: usize,
+        out: *mut f64,
+    ) {
+        let tup = (self::values(values, values_len)?, self::values(rates, rates_len)?);
+        write(out, NetPresentValueSchedule::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Future value of an irregular stream of payments, see `FutureValueStream`
+    fn rf_fvstream(rate: f64, pmts: *const f64, len: usize, pv: f64, when: i32, out: *mut f64) {
+        let tup = (rate, values(pmts, len)?, pv, when_type(when)?);
+        write(out, FutureValueStream::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Payback period, see `PaybackPeriod`
+    fn rf_payback(values: *const f64, len: usize, out: *mut f64, has_value: *mut bool) {
+        let values = self::values(values, len)?;
+        write_option(out, has_value, PaybackPeriod::from_vec(values).get().map_err(status)?)
+    }
+
+    /// Discounted payback period, see `DiscountedPaybackPeriod`
+    fn rf_discounted_payback(
+        values: *const f64,
+        len: usize,
+        rate: f64,
+        out: *mut f64,
+        has_value: *mut bool,
+    ) {
+        let tup = (self::values(values, len)?, rate);
+        let res = DiscountedPaybackPeriod::from_tuple(tup).get().map_err(status)?;
+        write_option(out, has_value, res)
+    }
+
+    /// Profitability index, see `ProfitabilityIndex`
+    fn rf_pi(values: *const f64, len: usize, rate: f64, out: *mut f64, has_value: *mut bool) {
+        let tup = (self::values(values, len)?, rate);
+        write_option(out, has_value, ProfitabilityIndex::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Benefit-cost ratio, see `BenefitCostRatio`
+    fn rf_bcr(values: *const f64, len: usize, rate: f64, out: *mut f64, has_value: *mut bool) {
+        let tup = (self::values(values, len)?, rate);
+        write_option(out, has_value, BenefitCostRatio::from_tuple(tup).get().map_err(status)?)
+    }
+
+    /// Equivalent annual annuity, see `EquivalentAnnualAnnuity`
+    fn rf_eaa(values: *const f64, len: usize, rate: f64, out: *mut f64, has_value: *mut bool) {
+        let tup = (self::values(values, len)?, rate);
+        let res = EquivalentAnnualAnnuity::from_tuple(tup).get().map_err(status)?;
+        write_option(out, has_value, res)
+    }
+
+    /// Equivalent annual cost, see `EquivalentAnnualCost`
+    fn rf_eac(values: *const f64, len: usize, rate: f64, out: *mut f64, has_value: *mut bool) {
+        let tup = (self::values(values, len)?, rate);
+        let res = EquivalentAnnualCost::from_tuple(tup).get().map_err(status)?;
+        write_option(out, has_value, res)
+    }
+
+    /// Amortization schedule of an adjustable-rate loan, see `AdjustableRateLoan`.
+    /// The number of rows is written to `written`, and if `rows_len` is too small
+    /// `RF_BUFFER_TOO_SMALL` is returned with the number of rows required
+    fn rf_arm(
+        rate: f64,
+        nper: u32,
+        pv: f64,
+        fv: f64,
+        when: i32,
+        first_reset: u32,
+        reset_freq: u32,
+        index: *const f64,
+        index_len: usize,
+        margin: f64,
+        periodic_cap: f64,
+        lifetime_cap: f64,
+        lifetime_floor: f64,
+        rows: *mut CAmortizationRow,
+        rows_len: usize,
+        written: *mut usize,
+    ) {
+        let arm = AdjustableRateLoan::from_tuple((
+            rate,
+            nper,
+            pv,
+            fv,
+            when_type(when)?,
+            first_reset,
+            reset_freq,
+            values(index, index_len)?,
+            margin,
+            periodic_cap,
+            lifetime_cap,
+            lifetime_floor,
+        ));
+        let schedule = arm.get().map_err(status)?;
+        write(written, schedule.len())?;
+        if rows_len < schedule.len() {
+            return Err(RF_BUFFER_TOO_SMALL);
+        }
+        if rows.is_null() && !schedule.is_empty() {
+            return Err(RF_NULL_POINTER);
+        }
+        for (i, row) in schedule.into_iter().enumerate() {
+            *rows.add(i) = CAmortizationRow {
+                per: row.per,
+                rate: row.rate,
+                pmt: row.pmt,
+                ipmt: row.ipmt,
+                ppmt: row.ppmt,
+                balance: row.balance,
+            };
+        }
+        Ok(())
+    }
+}
+
+/// Generate the C header `include/rfinancial.h` declaring every function of this module
+pub fn header() -> String {
+    let mut header = String::from(
+        "// Generated by `rfinancial::capi::header`, do not edit\n\
+         #ifndef RFINANCIAL_H\n\
+         #define RFINANCIAL_H\n\n\
+         #include <stdbool.h>\n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\n\
+         #ifdef __cplusplus\n\
+         extern \"C\" {\n\
+         #endif\n\n\
+         /// Status code returned by every function\n\
+         typedef int32_t rf_status;\n\n",
+    );
+    for (name, code, doc) in STATUSES {
+        header += &format!("/// {}\n#define {} {}\n", doc, name, code);
+    }
+    header += "\n";
+    header += ROW;
+    for prototype in PROTOTYPES {
+        header += "\n";
+        header += prototype;
+    }
+    header += "\n#ifdef __cplusplus\n}\n#endif\n\n#endif // RFINANCIAL_H\n";
+    header
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn test_capi_tvm() {
+        let mut out = 0.0;
+        let res = unsafe { rf_pmt(0.075 / 12.0, 12 * 15, 200000.0, 0.0, 0, &mut out) };
+        assert_eq!(res, RF_OK);
+        // npf.pmt(0.075 / 12, 12 * 15, 200000)
+        let tgt = -1854.0247200054619;
+        assert!(float_close(out, tgt, RTOL, ATOL), "{} v.s. {}", out, tgt);
+
+        let mut has_value = true;
+        let res = unsafe { rf_ipmt(0.01, 0, 24, 2000.0, 0.0, 0, &mut out, &mut has_value) };
+        assert_eq!(res, RF_OK);
+        assert!(!has_value && out.is_nan());
+    }
+
+    #[test]
+    fn test_capi_errors() {
+        let mut out = 0.0;
+        let res = unsafe { rf_fv(0.075, 20, -2000.0, 0.0, 2, &mut out) };
+        assert_eq!(res, RF_PARA_ERROR);
+
+        let res = unsafe { rf_fv(0.075, 20, -2000.0, 0.0, 0, std::ptr::null_mut()) };
+        assert_eq!(res, RF_NULL_POINTER);
+
+        let res = unsafe { rf_npv(std::ptr::null(), 3, 0.1, &mut out) };
+        assert_eq!(res, RF_NULL_POINTER);
+
+        // neither out-parameter is written if one of them is null
+        let values = [-100.0, 39.0, 59.0, 55.0, 20.0];
+        let mut has_value = false;
+        let res = unsafe { rf_irr(values.as_ptr(), 5, std::ptr::null_mut(), &mut has_value) };
+        assert_eq!((res, has_value), (RF_NULL_POINTER, false));
+        let res = unsafe { rf_irr(values.as_ptr(), 5, &mut out, std::ptr::null_mut()) };
+        assert_eq!((res, out), (RF_NULL_POINTER, 0.0));
+
+        let (values, rates) = ([-100.0, 60.0, 60.0], [0.1, 0.1]);
+        let res = unsafe { rf_npvschedule(values.as_ptr(), 3, rates.as_ptr(), 2, &mut out) };
+        assert_eq!(res, RF_PARA_ERROR);
+    }
+
+    #[test]
+    fn test_capi_cash_flows() {
+        let values = [-100.0, 39.0, 59.0, 55.0, 20.0];
+        let (mut out, mut has_value) = (0.0, false);
+        let res = unsafe { rf_irr(values.as_ptr(), values.len(), &mut out, &mut has_value) };
+        assert_eq!(res, RF_OK);
+        let tgt = InternalRateReturn::from_vec(values.to_vec()).get().unwrap();
+        assert_eq!((has_value, Some(out)), (true, tgt));
+
+        let res = unsafe { rf_npv(values.as_ptr(), values.len(), 0.281, &mut out) };
+        assert_eq!(res, RF_OK);
+        let tgt = NetPresentValue::from_tuple((values.to_vec(), 0.281))
+            .get()
+            .unwrap();
+        assert_eq!(out, tgt);
+    }
+
+    #[test]
+    fn test_capi_arm() {
+        let mut rows = [CAmortizationRow::default(); 12];
+        let mut written = 0;
+        let arm = |rows: &mut [CAmortizationRow], written: &mut usize| unsafe {
+            rf_arm(
+                0.01,
+                12,
+                1000.0,
+                0.0,
+                0,
+                12,
+                1,
+                std::ptr::null(),
+                0,
+                0.0,
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                rows.as_mut_ptr(),
+                rows.len(),
+                written,
+            )
+        };
+        assert_eq!(arm(&mut rows[..6], &mut written), RF_BUFFER_TOO_SMALL);
+        assert_eq!(written, 12);
+
+        assert_eq!(arm(&mut rows, &mut written), RF_OK);
+        let pmt = Payment::from_tuple((0.01, 12, 1000.0, 0.0, WhenType::End))
+            .get()
+            .unwrap();
+        assert_eq!(rows[11].per, 12);
+        assert!(rows.iter().all(|r| float_close(r.pmt, pmt, RTOL, ATOL)));
+        assert!(float_close(rows[11].balance, 0.0, RTOL, ATOL));
+    }
+
+    #[allow(dead_code)]
+    mod panics {
+        use super::*;
+
+        capi! {
+            /// Panic unconditionally
+            fn rf_test_panic() {
+                panic!("caught by `capi!`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_capi_panic() {
+        assert_eq!(unsafe { panics::rf_test_panic() }, RF_PANIC);
+    }
+
+    #[test]
+    fn test_capi_header() {
+        let header = header();
+        assert!(header.contains(
+            "rf_status rf_npv(const double *values, size_t len, double rate, double *out);"
+        ));
+        assert!(header.contains("#define RF_BUFFER_TOO_SMALL 5"));
+        assert_eq!(header.matches("rf_status rf_").count(), PROTOTYPES.len());
+    }
+}
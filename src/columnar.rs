@@ -0,0 +1,468 @@
+//! # Columnar loan tapes over Apache Arrow
+//! Functions built with the `arrow` feature, computing payments of a whole loan tape at once from Arrow arrays or
+//! a `RecordBatch`, e.g. read from Parquet. Each row is a loan given by the columns
+//! * `rate` : an interest rate compounded once per period
+//! * `nper` : number of compounding periods, a whole number
+//! * `pv` : a present value
+//! * `fv` : a future value, `0` if the column is missing
+//! * `when` : `0`/`"end"`, `1`/`"begin"`, `0.5`/`"middle"` or another offset in `[0, 1]`, see [`WhenType`], end
+//!   of periods if the column is missing
+//! * `per` : the period of interest, a whole number, `1` if the column is missing
+//!
+//! and gets the columns
+//! * `pmt` : payment against loan principal plus interest, see [`Payment`]
+//! * `ipmt` : interest portion of the payment in period `per`, see [`InterestPayment`]
+//! * `ppmt` : principal portion of the payment in period `per`, see [`PrincipalPayment`]
+//! * `balance` : principal remaining after `per` payments, with the sign of `pv`
+//!
+//! Numbers may be of any integer or float type, and `when` may also be a string column, including string views and
+//! dictionary-encoded strings. How a null in any input is
+//! treated is chosen with [`Nulls`]; an output is also null where there is no solution, e.g. in period `0`.
+//! Polars frames are passed through the Arrow C data interface or Parquet.
+//!
+//! ## Example
+//! ```rust
+//! use std::sync::Arc;
+//! use arrow_array::{ArrayRef, Float64Array, RecordBatch, UInt32Array};
+//! use rfinancial::columnar::{self, Nulls};
+//! let batch = RecordBatch::try_from_iter([
+//!     ("rate", Arc::new(Float64Array::from(vec![Some(0.05 / 12.0), None])) as ArrayRef),
+//!     ("nper", Arc::new(UInt32Array::from(vec![360, 180])) as ArrayRef),
+//!     ("pv", Arc::new(Float64Array::from(vec![250000.0, 100000.0])) as ArrayRef),
+//! ])
+//! .unwrap();
+//! // the 2nd loan without a rate gets nulls
+//! let batch = columnar::append_loan_columns(&batch, Nulls::Propagate).unwrap();
+//! println!("{:?}", batch.column_by_name("pmt"));
+//! // or an error for its row
+//! assert!(columnar::append_loan_columns(&batch, Nulls::Reject).is_err());
+//! ```
+use crate::{Error, FutureValue, InterestPayment, Payment, PrincipalPayment, Result, WhenType};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use std::sync::Arc;
+
+/// How a row with a null in any of its inputs is treated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Nulls {
+    /// all outputs of the row are null
+    Propagate,
+    /// the whole call fails with an error naming the column and row
+    Reject,
+}
+
+/// Output columns of [`loan_columns`], one element for each row
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoanColumns {
+    pub pmt: Float64Array,
+    pub ipmt: Float64Array,
+    pub ppmt: Float64Array,
+    pub balance: Float64Array,
+}
+
+fn column_error(name: &str, row: usize, msg: impl std::fmt::Display) -> Error {
+    Error::ParaError(format!("{}: row {}: {}", name, row, msg))
+}
+
+/// A column of any integer or float type as `f64`
+fn floats(col: &dyn Array, name: &str) -> Result<Vec<Option<f64>>> {
+    macro_rules! cast {
+        ($($t:ty),+) => {
+            $(if let Some(arr) = col.as_primitive_opt::<$t>() {
+                return Ok(arr.iter().map(|v| v.map(|v| v as f64)).collect());
+            })+
+        };
+    }
+    cast!(
+        Float64Type,
+        Float32Type,
+        Int8Type,
+        Int16Type,
+        Int32Type,
+        Int64Type
+    );
+    cast!(UInt8Type, UInt16Type, UInt32Type, UInt64Type);
+    Err(Error::ParaError(format!(
+        "{}: expected a numeric column, got {}",
+        name,
+        col.data_type()
+    )))
+}
+
+/// A column of whole non-negative numbers as `u32`
+fn wholes(col: &dyn Array, name: &str) -> Result<Vec<Option<u32>>> {
+    let whole = |(row, v): (usize, Option<f64>)| match v {
+        Some(n) if n >= 0.0 && n.fract() == 0.0 && n <= u32::MAX as f64 => Ok(Some(n as u32)),
+        Some(n) => Err(column_error(
+            name,
+            row,
+            format!("not a whole number: {}", n),
+        )),
+        None => Ok(None),
+    };
+    floats(col, name)?
+        .into_iter()
+        .enumerate()
+        .map(whole)
+        .collect()
+}
+
+/// A string column, also as string views or dictionary-encoded, or `None` for another type
+fn strings(col: &dyn Array) -> Option<Vec<Option<&str>>> {
+    match col.data_type() {
+        DataType::Utf8 => Some(col.as_string::<i32>().iter().collect()),
+        DataType::LargeUtf8 => Some(col.as_string::<i64>().iter().collect()),
+        DataType::Utf8View => Some(col.as_string_view().iter().collect()),
+        DataType::Dictionary(_, _) => {
+            let dict = col.as_any_dictionary();
+            let values = strings(dict.values().as_ref())?;
+            if values.is_empty() {
+                // all keys are null
+                return Some(vec![None; col.len()]);
+            }
+            let keys = dict.normalized_keys();
+            Some(
+                (0..col.len())
+                    .map(|row| {
+                        if dict.keys().is_null(row) {
+                            None
+                        } else {
+                            values[keys[row]]
+                        }
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// A column of offsets such as `0`/`1` or of `"end"`/`"begin"`/`"middle"` as `WhenType`
+fn whens(col: &dyn Array, name: &str) -> Result<Vec<Option<WhenType>>> {
+    let strs = strings(col);
+    let when = |(row, s): (usize, Option<&str>)| match s {
+        Some(s) => s
+            .parse()
+            .map(Some)
+            .map_err(|_| column_error(name, row, format!("invalid `when`: {}", s))),
+        None => Ok(None),
+    };
+    if let Some(strs) = strs {
+        return strs.into_iter().enumerate().map(when).collect();
+    }
+    let when = |(row, v): (usize, Option<f64>)| match v {
+        Some(v) => WhenType::from_offset(v)
+            .map(Some)
+            .ok_or_else(|| column_error(name, row, format!("invalid `when`: {}", v))),
+        None => Ok(None),
+    };
+    floats(col, name)?
+        .into_iter()
+        .enumerate()
+        .map(when)
+        .collect()
+}
+
+/// An optional column, or `default` for each of `len` rows
+fn or_default<T: Clone>(col: Option<Vec<Option<T>>>, len: usize, default: T) -> Vec<Option<T>> {
+    col.unwrap_or_else(|| vec![Some(default); len])
+}
+
+/// Compute the output columns of a loan tape from its input columns, see the [module](self) documentation
+#[allow(clippy::too_many_arguments)]
+pub fn loan_columns(
+    rate: &dyn Array,
+    nper: &dyn Array,
+    pv: &dyn Array,
+    fv: Option<&dyn Array>,
+    when: Option<&dyn Array>,
+    per: Option<&dyn Array>,
+    nulls: Nulls,
+) -> Result<LoanColumns> {
+    let len = rate.len();
+    let inputs = [Some(nper), Some(pv), fv, when, per];
+    if let Some(col) = inputs.into_iter().flatten().find(|col| col.len() != len) {
+        return Err(Error::ParaError(format!(
+            "columns of different lengths: {} and {}",
+            len,
+            col.len()
+        )));
+    }
+
+    let rate = floats(rate, "rate")?;
+    let nper = wholes(nper, "nper")?;
+    let pv = floats(pv, "pv")?;
+    let fv = or_default(fv.map(|c| floats(c, "fv")).transpose()?, len, 0.0);
+    let when = or_default(
+        when.map(|c| whens(c, "when")).transpose()?,
+        len,
+        WhenType::End,
+    );
+    let per = or_default(per.map(|c| wholes(c, "per")).transpose()?, len, 1);
+
+    let mut out = [
+        Vec::with_capacity(len),
+        Vec::with_capacity(len),
+        Vec::with_capacity(len),
+        Vec::with_capacity(len),
+    ];
+    for row in 0..len {
+        let (Some(rate), Some(nper), Some(pv), Some(fv), Some(when), Some(per)) = (
+            rate[row],
+            nper[row],
+            pv[row],
+            fv[row],
+            when[row].clone(),
+            per[row],
+        ) else {
+            if nulls == Nulls::Reject {
+                let cols = [
+                    ("rate", rate[row].is_none()),
+                    ("nper", nper[row].is_none()),
+                    ("pv", pv[row].is_none()),
+                    ("fv", fv[row].is_none()),
+                    ("when", when[row].is_none()),
+                    ("per", per[row].is_none()),
+                ];
+                let name = cols.iter().find(|(_, null)| *null).map_or("", |c| c.0);
+                return Err(column_error(name, row, "null"));
+            }
+            out.iter_mut().for_each(|col| col.push(None));
+            continue;
+        };
+
+        let row_error = |err: Error| Error::OtherError(format!("row {}: {}", row, err));
+        let pmt = Payment::from_tuple((rate, nper, pv, fv, when.clone()))
+            .get()
+            .map_err(row_error)?;
+        let tup = (rate, per, nper, pv, fv, when.clone());
+        let ipmt = InterestPayment::from_tuple(tup.clone())
+            .get()
+            .map_err(row_error)?;
+        let ppmt = PrincipalPayment::from_tuple(tup).get().map_err(row_error)?;
+        let balance = -FutureValue::from_tuple((rate, per, pmt, pv, when))
+            .get()
+            .map_err(row_error)?;
+
+        out[0].push(Some(pmt));
+        out[1].push(ipmt);
+        out[2].push(ppmt);
+        out[3].push(Some(balance));
+    }
+
+    let [pmt, ipmt, ppmt, balance] = out.map(Float64Array::from);
+    Ok(LoanColumns {
+        pmt,
+        ipmt,
+        ppmt,
+        balance,
+    })
+}
+
+/// Append the columns `pmt`, `ipmt`, `ppmt` and `balance` to a loan tape with the columns `rate`, `nper`, `pv` and
+/// optionally `fv`, `when` and `per`, see the [module](self) documentation
+pub fn append_loan_columns(batch: &RecordBatch, nulls: Nulls) -> Result<RecordBatch> {
+    let column = |name: &str| batch.column_by_name(name).map(|col| col.as_ref());
+    let required = |name: &str| {
+        column(name).ok_or_else(|| Error::ParaError(format!("{}: missing column", name)))
+    };
+    let out = loan_columns(
+        required("rate")?,
+        required("nper")?,
+        required("pv")?,
+        column("fv"),
+        column("when"),
+        column("per"),
+        nulls,
+    )?;
+
+    let outputs: [(&str, ArrayRef); 4] = [
+        ("pmt", Arc::new(out.pmt)),
+        ("ipmt", Arc::new(out.ipmt)),
+        ("ppmt", Arc::new(out.ppmt)),
+        ("balance", Arc::new(out.balance)),
+    ];
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    let mut columns = batch.columns().to_vec();
+    for (name, col) in outputs {
+        if column(name).is_some() {
+            return Err(Error::ParaError(format!("{}: column already exists", name)));
+        }
+        fields.push(Field::new(name, DataType::Float64, true));
+        columns.push(col);
+    }
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|err| Error::OtherError(err.to_string()))
+}
+
+#[allow(unused_imports)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use arrow_array::types::Int32Type;
+    use arrow_array::{DictionaryArray, Int64Array, StringArray, StringViewArray, UInt32Array};
+
+    fn tape() -> RecordBatch {
+        RecordBatch::try_from_iter([
+            (
+                "rate",
+                Arc::new(Float64Array::from(vec![
+                    Some(0.05 / 12.0),
+                    None,
+                    Some(0.01),
+                ])) as ArrayRef,
+            ),
+            (
+                "nper",
+                Arc::new(Int64Array::from(vec![360, 180, 24])) as ArrayRef,
+            ),
+            (
+                "pv",
+                Arc::new(Float64Array::from(vec![250000.0, 1e5, 2000.0])) as ArrayRef,
+            ),
+            (
+                "when",
+                Arc::new(StringArray::from(vec!["end", "end", "begin"])) as ArrayRef,
+            ),
+            (
+                "per",
+                Arc::new(UInt32Array::from(vec![1, 1, 0])) as ArrayRef,
+            ),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_columnar_append() {
+        let batch = append_loan_columns(&tape(), Nulls::Propagate).unwrap();
+        assert_eq!(batch.num_columns(), 9);
+        let col = |name: &str| {
+            batch
+                .column_by_name(name)
+                .unwrap()
+                .as_primitive::<Float64Type>()
+                .clone()
+        };
+        let (pmt, ipmt, ppmt, balance) = (col("pmt"), col("ipmt"), col("ppmt"), col("balance"));
+
+        // the 1st loan agrees with the calculators
+        let tgt = Payment::from_tuple((0.05 / 12.0, 360, 250000.0, 0.0, WhenType::End))
+            .get()
+            .unwrap();
+        assert_eq!(pmt.value(0), tgt);
+        let tup = (0.05 / 12.0, 1, 360, 250000.0, 0.0, WhenType::End);
+        let tgt = InterestPayment::from_tuple(tup).get().unwrap().unwrap();
+        assert_eq!(ipmt.value(0), tgt);
+        assert!(float_close(
+            ipmt.value(0) + ppmt.value(0),
+            pmt.value(0),
+            RTOL,
+            ATOL
+        ));
+        let tgt = 250000.0 + ppmt.value(0);
+        assert!(
+            float_close(balance.value(0), tgt, RTOL, ATOL),
+            "{}",
+            balance.value(0)
+        );
+
+        // the 2nd loan has no rate
+        assert!(pmt.is_null(1) && ipmt.is_null(1) && ppmt.is_null(1) && balance.is_null(1));
+
+        // the 3rd loan has a payment but no interest in period 0
+        assert!(pmt.is_valid(2) && ipmt.is_null(2) && ppmt.is_null(2));
+        assert_eq!(balance.value(2), 2000.0);
+    }
+
+    #[test]
+    fn test_columnar_middle() {
+        let rate = Float64Array::from(vec![0.01, 0.01]);
+        let nper = UInt32Array::from(vec![24, 24]);
+        let pv = Float64Array::from(vec![2000.0, 2000.0]);
+        let when = StringArray::from(vec!["end", "middle"]);
+        let per = UInt32Array::from(vec![3, 3]);
+        let out = loan_columns(
+            &rate,
+            &nper,
+            &pv,
+            None,
+            Some(&when),
+            Some(&per),
+            Nulls::Reject,
+        )
+        .unwrap();
+
+        let tup = (0.01, 3, 24, 2000.0, 0.0, WhenType::Middle);
+        let tgt = InterestPayment::from_tuple(tup).get().unwrap().unwrap();
+        assert_eq!(out.ipmt.value(1), tgt);
+        assert!(float_close(
+            out.ipmt.value(1) + out.ppmt.value(1),
+            out.pmt.value(1),
+            RTOL,
+            ATOL
+        ));
+        // paid half a period earlier, with less interest than at the end
+        assert!(out.ipmt.value(0) < out.ipmt.value(1) && out.ipmt.value(1) < 0.0);
+    }
+
+    #[test]
+    fn test_columnar_when_strings() {
+        let strs = vec![Some("begin"), None, Some("Middle"), Some("begin")];
+        let tgt = vec![
+            Some(WhenType::Begin),
+            None,
+            Some(WhenType::Middle),
+            Some(WhenType::Begin),
+        ];
+        let view = StringViewArray::from(strs.clone());
+        assert_eq!(whens(&view, "when").unwrap(), tgt);
+        let dict: DictionaryArray<Int32Type> = strs.into_iter().collect();
+        assert_eq!(whens(&dict, "when").unwrap(), tgt);
+
+        // an invalid string is reported for its row rather than its dictionary key
+        let dict: DictionaryArray<Int32Type> = vec!["end", "end", "later"].into_iter().collect();
+        let res = whens(&dict, "when");
+        let msg = "when: row 2: invalid `when`: later";
+        assert!(matches!(res, Err(Error::ParaError(m)) if m == msg));
+    }
+
+    #[test]
+    fn test_columnar_errors() {
+        let res = append_loan_columns(&tape(), Nulls::Reject);
+        assert!(matches!(res, Err(Error::ParaError(msg)) if msg == "rate: row 1: null"));
+
+        // a fractional `nper`
+        let nper = Float64Array::from(vec![12.0, 12.5]);
+        let rate = Float64Array::from(vec![0.01, 0.01]);
+        let pv = Float64Array::from(vec![100.0, 100.0]);
+        let res = loan_columns(&rate, &nper, &pv, None, None, None, Nulls::Propagate);
+        let msg = "nper: row 1: not a whole number: 12.5";
+        assert!(matches!(res, Err(Error::ParaError(m)) if m == msg));
+
+        // columns of different lengths and a missing column
+        let res = loan_columns(
+            &rate,
+            &nper.slice(0, 1),
+            &pv,
+            None,
+            None,
+            None,
+            Nulls::Propagate,
+        );
+        assert!(res.is_err());
+        let batch = tape().project(&[0, 2]).unwrap();
+        assert!(append_loan_columns(&batch, Nulls::Propagate).is_err());
+
+        // the output columns are already there
+        let batch = append_loan_columns(&tape(), Nulls::Propagate).unwrap();
+        assert!(append_loan_columns(&batch, Nulls::Propagate).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_vecf64, Error, ParaMap};
+use crate::{InternalRateReturn, NetPresentValue, Result};
+use alloc::vec::Vec;
+
+/// Number of grid points when scanning for rates where a net present value changes sign
+const SCAN_STEPS: u32 = 20000;
+/// Scan `1+rate` from `1/SCAN_RANGE` to `SCAN_RANGE`, i.e. `rate` from `-0.99` to `99`
+const SCAN_RANGE: f64 = 100.0;
+
+/// # Compare two mutually exclusive projects
+/// When `npv` and `irr` rank two projects differently, the crossover rate where both have the same `npv` tells which
+/// project is better at a given discount rate
+///
+/// ## Parameters
+/// * `values_a` : cash flow of project A, assume first payment is made at present, i.e. `t=0`
+/// * `values_b` : cash flow of project B; the shorter one is padded with zeros
+///
+/// ## Return:
+/// * `crossover`: all rates where both projects have the same `npv`, in ascending order
+/// * `incremental_irr`: the `irr` of `values_a - values_b` or `None`
+/// * `profile`: (`rate`, `npv_a`, `npv_b`) over given rates
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let cmp = ProjectComparison::from_tuple((vec![-1000.0, 800.0, 300.0], vec![-1000.0, 100.0, 1100.0]));
+/// println!("{:#?}'s crossover is {:?}", cmp, cmp.get_crossover());
+/// println!("{:#?}'s incremental irr is {:?}", cmp, cmp.get_incremental_irr());
+/// println!("{:#?}'s profile is {:?}", cmp, cmp.get_profile(&[0.0, 0.05, 0.1]));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProjectComparison {
+    values_a: Vec<f64>,
+    values_b: Vec<f64>,
+}
+
+/// Evaluate npv of `values` at `rate`
+fn npv(values: &[f64], rate: f64) -> Result<f64> {
+    NetPresentValue::from_slice((values, rate)).get()
+}
+
+/// Bisect `[lo, hi]`, where the npv of `values` changes sign from `f_lo` at `lo`
+fn bisect(values: &[f64], lo: f64, hi: f64, f_lo: f64) -> Result<f64> {
+    let (mut a, mut b, mut f_a) = (lo, hi, f_lo);
+    for _ in 0..100 {
+        let m = 0.5 * (a + b);
+        let f_m = npv(values, m)?;
+        if f_m == 0.0 || (b - a) < 1e-15 {
+            return Ok(m);
+        }
+        if f_a * f_m < 0.0 {
+            b = m;
+        } else {
+            a = m;
+            f_a = f_m;
+        }
+    }
+    Ok(0.5 * (a + b))
+}
+
+/// Rate in `[lo, hi]` where `|npv|` of `values` is smallest, by golden-section search
+fn minimize(values: &[f64], lo: f64, hi: f64) -> Result<f64> {
+    let g = 0.5 * (5.0_f64.sqrt() - 1.0);
+    let (mut a, mut b) = (lo, hi);
+    let (mut c, mut d) = (b - g * (b - a), a + g * (b - a));
+    let (mut f_c, mut f_d) = (npv(values, c)?.abs(), npv(values, d)?.abs());
+    for _ in 0..100 {
+        if (b - a) < 1e-15 {
+            break;
+        }
+        if f_c < f_d {
+            (b, d, f_d) = (d, c, f_c);
+            c = b - g * (b - a);
+            f_c = npv(values, c)?.abs();
+        } else {
+            (a, c, f_c) = (c, d, f_d);
+            d = a + g * (b - a);
+            f_d = npv(values, d)?.abs();
+        }
+    }
+    Ok(0.5 * (a + b))
+}
+
+/// Find all rates in `[-0.99, 99]` where the `npv` of `values` is 0
+/// * a root where the `npv` changes sign is bracketed on a grid of rates and bisected
+/// * a root where the `npv` only touches 0 is a local minimum of `|npv|` on the grid, refined by golden-section
+///   search and kept if `|npv|` is below `1e-9` of the sum of `|values|` there
+/// * all-zero `values`, e.g. the difference of identical projects, are 0 at every rate and have no roots
+pub(crate) fn npv_roots(values: &[f64]) -> Result<Vec<f64>> {
+    let step = SCAN_RANGE.ln() * 2.0 / SCAN_STEPS as f64;
+    let rate_at = |i: usize| (step * i as f64).exp() / SCAN_RANGE - 1.0;
+
+    let mut roots = Vec::new();
+    if values.iter().all(|&v| v == 0.0) {
+        return Ok(roots);
+    }
+    let tol = 1e-9 * values.iter().map(|v| v.abs()).sum::<f64>();
+    let f = (0..=SCAN_STEPS as usize)
+        .map(|i| npv(values, rate_at(i)))
+        .collect::<Result<Vec<f64>>>()?;
+    let last = f.len() - 1;
+    for i in 0..=last {
+        if f[i] == 0.0 {
+            roots.push(rate_at(i));
+        } else if i < last && f[i] * f[i + 1] < 0.0 {
+            roots.push(bisect(values, rate_at(i), rate_at(i + 1), f[i])?);
+        } else if 0 < i
+            && i < last
+            && f[i - 1] * f[i] > 0.0
+            && f[i] * f[i + 1] > 0.0
+            && f[i].abs() < f[i - 1].abs()
+            && f[i].abs() <= f[i + 1].abs()
+        {
+            let rate = minimize(values, rate_at(i - 1), rate_at(i + 1))?;
+            if npv(values, rate)?.abs() <= tol {
+                roots.push(rate);
+            }
+        }
+    }
+
+    Ok(roots)
+}
+
+impl ProjectComparison {
+    /// Instantiate a `ProjectComparison` instance from a tuple of (`values_a`, `values_b`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, Vec<f64>)) -> Self {
+        ProjectComparison {
+            values_a: tup.0,
+            values_b: tup.1,
+        }
+    }
+
+    /// Instantiate a `ProjectComparison` instance from a hash map with keys of (`values_a`, `values_b`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `ProjectComparison` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values_a = get_vecf64(&map, "values_a").map_err(op)?;
+        let values_b = get_vecf64(&map, "values_b").map_err(op)?;
+        Ok(ProjectComparison { values_a, values_b })
+    }
+
+    /// Cash flow of `values_a - values_b`, padding the shorter one with zeros
+    fn difference(&self) -> Vec<f64> {
+        let n = self.values_a.len().max(self.values_b.len());
+        (0..n)
+            .map(|p| {
+                self.values_a.get(p).copied().unwrap_or(0.0)
+                    - self.values_b.get(p).copied().unwrap_or(0.0)
+            })
+            .collect()
+    }
+
+    /// Get all crossover rates from an instance of `ProjectComparison`
+    pub fn get_crossover(&self) -> Result<Vec<f64>> {
+        npv_roots(&self.difference())
+    }
+
+    /// Get the incremental irr, i.e. the irr of `values_a - values_b`, from an instance of `ProjectComparison`
+    pub fn get_incremental_irr(&self) -> Result<Option<f64>> {
+        InternalRateReturn::from_vec(self.difference()).get()
+    }
+
+    /// Get (`rate`, `npv_a`, `npv_b`) at each of `rates` from an instance of `ProjectComparison`
+    pub fn get_profile(&self, rates: &[f64]) -> Result<Vec<(f64, f64, f64)>> {
+        rates
+            .iter()
+            .map(|&rate| Ok((rate, npv(&self.values_a, rate)?, npv(&self.values_b, rate)?)))
+            .collect()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_compare_from_tuple() {
+        // difference is [0, 700, -800], so npv are equal at 800/700 - 1
+        let cmp = ProjectComparison::from_tuple((
+            vec![-1000.0, 800.0, 300.0],
+            vec![-1000.0, 100.0, 1100.0],
+        ));
+        let res = cmp.get_crossover().unwrap();
+        let tgt = 800.0 / 700.0 - 1.0;
+        assert_eq!(res.len(), 1, "{:#?}", res);
+        assert!(
+            float_close(res[0], tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        let a = NetPresentValue::from_tuple((vec![-1000.0, 800.0, 300.0], res[0]))
+            .get()
+            .unwrap();
+        let b = NetPresentValue::from_tuple((vec![-1000.0, 100.0, 1100.0], res[0]))
+            .get()
+            .unwrap();
+        assert!(float_close(a, b, RTOL, ATOL), "{:#?} v.s. {:#?}", a, b);
+    }
+
+    #[test]
+    fn test_compare_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values_a".to_string(),
+            ParaType::VecF64(vec![-1000.0, 800.0, 300.0]),
+        );
+        map.insert(
+            "values_b".to_string(),
+            ParaType::VecF64(vec![-1000.0, 100.0, 1100.0]),
+        );
+        let cmp = ProjectComparison::from_map(map).unwrap();
+        let res = cmp.get_incremental_irr().unwrap().unwrap();
+        let tgt = 800.0 / 700.0 - 1.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_compare_padding() {
+        // B is padded to [-500, 600, 0], so difference is [-500, 200, 300]
+        let cmp = ProjectComparison::from_tuple((vec![-1000.0, 800.0, 300.0], vec![-500.0, 600.0]));
+        let res = cmp.get_crossover().unwrap();
+        // -500 + 200x + 300x^2 = 0 -> x = 1, i.e. rate is 0
+        assert_eq!(res.len(), 1, "{:#?}", res);
+        assert!(
+            float_close(res[0], 0.0, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_compare_multiple_crossover() {
+        // difference is [-1, 5, -6], i.e. -1 + 5x - 6x^2 = 0 at x = 1/2 and 1/3, so rates are 1 and 2
+        let cmp = ProjectComparison::from_tuple((vec![-1.0, 5.0, -6.0], vec![0.0]));
+        let res = cmp.get_crossover().unwrap();
+        let tgt = [1.0, 2.0];
+        assert_eq!(res.len(), tgt.len(), "{:#?}", res);
+        for (r, t) in res.iter().zip(tgt.iter()) {
+            assert!(
+                float_close(*r, *t, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_no_crossover() {
+        // A dominates B at every rate
+        let cmp = ProjectComparison::from_tuple((
+            vec![-1000.0, 600.0, 600.0],
+            vec![-1000.0, 500.0, 500.0],
+        ));
+        assert!(cmp.get_crossover().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_identical() {
+        // the difference is 0 at every rate
+        let cmp = ProjectComparison::from_tuple((
+            vec![-1000.0, 800.0, 300.0],
+            vec![-1000.0, 800.0, 300.0],
+        ));
+        assert!(cmp.get_crossover().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_touching() {
+        // (1 - 1.1x)^2 at x = 1/(1+rate) only touches 0 at 10%
+        let cmp = ProjectComparison::from_tuple((vec![1.0, -2.2, 1.21], vec![0.0]));
+        let res = cmp.get_crossover().unwrap();
+        assert_eq!(res.len(), 1, "{:#?}", res);
+        assert!(float_close(res[0], 0.1, 1e-6, 1e-6), "{:#?}", res);
+
+        // a minimum of |npv| away from 0 is not a root
+        let cmp = ProjectComparison::from_tuple((vec![1.0, -2.2, 1.22], vec![0.0]));
+        assert!(cmp.get_crossover().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_profile() {
+        let cmp = ProjectComparison::from_tuple((
+            vec![-1000.0, 800.0, 300.0],
+            vec![-1000.0, 100.0, 1100.0],
+        ));
+        let res = cmp.get_profile(&[0.0, 0.1]).unwrap();
+        let tgt = [
+            (0.0, 100.0, 200.0),
+            (
+                0.1,
+                -1000.0 + 800.0 / 1.1 + 300.0 / 1.21,
+                -1000.0 + 100.0 / 1.1 + 1100.0 / 1.21,
+            ),
+        ];
+        for (r, t) in res.iter().zip(tgt.iter()) {
+            assert_eq!(r.0, t.0);
+            assert!(
+                float_close(r.1, t.1, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+            assert!(
+                float_close(r.2, t.2, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_err() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 800.0, 300.0]),
+        );
+        let cmp = ProjectComparison::from_map(map);
+        assert!(cmp.is_err());
+    }
+}
@@ -0,0 +1,530 @@
+//! # Cash flows and loan tapes in CSV
+//! Functions built with the `csv` feature, reading the inputs of the calculators from comma-separated values with a
+//! header line, and writing their results back. Columns are found by name in any order, other columns are ignored.
+//!
+//! A cash flow has one payment per line, in any order, with the columns
+//! * `amount` : the payment, negative for money paid out
+//! * `period` : the period of the payment, a whole number with `0` at present, or
+//! * `date` : the date of the payment as `YYYY-MM-DD`, the earliest date being at present and every other date a
+//!   whole number of periods of a [`Frequency`] later
+//!
+//! A payment is at most [`MAX_PERIOD`] periods after the present, as the cash flow has a value for every period.
+//!
+//! A loan tape has one loan per line with the columns
+//! * `rate` : an interest rate compounded once per period
+//! * `nper` : number of compounding periods, a whole number
+//! * `pv` : a present value
+//! * `fv` : a future value, `0` if the column is missing or the cell is empty
+//! * `when` : `0`/`"end"`, `1`/`"begin"`, `0.5`/`"middle"` or another offset in `[0, 1]`, see [`WhenType`], end of
+//!   periods if the column is missing or the cell is empty
+//!
+//! Bad data fails with an `Error::ParaError` naming the column and the line, e.g. `nper: line 3: not a whole
+//! number: 12.5`, and a malformed line, e.g. with a missing cell, with the message of the CSV parser.
+//!
+//! ## Example
+//! ```rust
+//! use rfinancial::csv::{self, Frequency};
+//! let flows = "date,amount\n2024-01-31,-1000\n2024-02-29,300\n2024-04-30,800\n";
+//! let flows = csv::read_cash_flows(flows.as_bytes(), Frequency::Monthly).unwrap();
+//! assert_eq!(flows.values(), vec![-1000.0, 300.0, 0.0, 800.0]);
+//! println!("npv is {:?} and irr is {:?}", flows.npv(0.01).get(), flows.irr().get());
+//!
+//! let tape = "rate,nper,pv,when\n0.004,360,250000,end\n0.01,24,2000,begin\n";
+//! let loans = csv::read_loans(tape.as_bytes()).unwrap();
+//! let mut out = Vec::new();
+//! csv::write_loans(&mut out, &loans).unwrap();
+//! println!("{}", String::from_utf8(out).unwrap());
+//! ```
+use crate::{
+    AmortizationRow, Date, Error, InternalRateReturn, NetPresentValue, ParaMap, ParaType, Payment,
+    Result, Table, WhenType,
+};
+use ::csv::{ErrorKind, ReaderBuilder, StringRecord, Writer};
+use std::io;
+
+/// The last period of a payment in a cash flow, to bound the length of [`CashFlows::values`]
+pub const MAX_PERIOD: u32 = 100_000;
+
+/// The length of a period, to place dated payments of a cash flow in periods
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Frequency {
+    #[default]
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+}
+
+impl Frequency {
+    fn months(self) -> i64 {
+        match self {
+            Frequency::Monthly => 1,
+            Frequency::Quarterly => 3,
+            Frequency::SemiAnnual => 6,
+            Frequency::Annual => 12,
+        }
+    }
+}
+
+/// # A cash flow read by [`read_cash_flows`]
+/// Payments in the order of their lines, several of them may fall in the same period
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CashFlows {
+    /// the period of each payment
+    pub periods: Vec<u32>,
+    /// the amount of each payment
+    pub amounts: Vec<f64>,
+}
+
+impl CashFlows {
+    /// The net payment of every period from `0` to the last one, `0` where there is no payment
+    pub fn values(&self) -> Vec<f64> {
+        let len = self.periods.iter().max().map_or(0, |&p| p as usize + 1);
+        let mut values = vec![0.0; len];
+        for (&per, &amount) in self.periods.iter().zip(self.amounts.iter()) {
+            values[per as usize] += amount;
+        }
+        values
+    }
+
+    /// A [`NetPresentValue`] of the cash flow at `rate`
+    pub fn npv(&self, rate: f64) -> NetPresentValue<'static> {
+        NetPresentValue::from_tuple((self.values(), rate))
+    }
+
+    /// An [`InternalRateReturn`] of the cash flow
+    pub fn irr(&self) -> InternalRateReturn<'static> {
+        InternalRateReturn::from_vec(self.values())
+    }
+
+    /// A `ParaMap` of the cash flow as `values`, to add e.g. `rate` to
+    pub fn to_map(&self) -> ParaMap {
+        ParaMap::from([("values".to_string(), ParaType::VecF64(self.values()))])
+    }
+}
+
+/// # A loan read by [`read_loans`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Loan {
+    pub rate: f64,
+    pub nper: u32,
+    pub pv: f64,
+    pub fv: f64,
+    pub when: WhenType,
+}
+
+impl Loan {
+    /// A [`Payment`] of the loan
+    pub fn payment(&self) -> Payment {
+        Payment::from_tuple((self.rate, self.nper, self.pv, self.fv, self.when.clone()))
+    }
+
+    /// A `ParaMap` of the loan as `rate`, `nper`, `pv`, `fv` and `when`, to add e.g. `per` to
+    pub fn to_map(&self) -> ParaMap {
+        ParaMap::from([
+            ("rate".to_string(), ParaType::F64(self.rate)),
+            ("nper".to_string(), ParaType::U32(self.nper)),
+            ("pv".to_string(), ParaType::F64(self.pv)),
+            ("fv".to_string(), ParaType::F64(self.fv)),
+            ("when".to_string(), ParaType::When(self.when.clone())),
+        ])
+    }
+}
+
+fn csv_error(err: ::csv::Error) -> Error {
+    match err.kind() {
+        ErrorKind::Io(_) => Error::OtherError(err.to_string()),
+        _ => Error::ParaError(err.to_string()),
+    }
+}
+
+fn cell_error(name: &str, line: u64, msg: impl std::fmt::Display) -> Error {
+    Error::ParaError(format!("{}: line {}: {}", name, line, msg))
+}
+
+/// The lines of a CSV with the position of each named column in them
+struct Lines {
+    headers: StringRecord,
+    records: Vec<StringRecord>,
+}
+
+impl Lines {
+    fn read<R: io::Read>(rdr: R) -> Result<Self> {
+        let mut rdr = ReaderBuilder::new().trim(::csv::Trim::All).from_reader(rdr);
+        let headers = rdr.headers().map_err(csv_error)?.clone();
+        let records = rdr
+            .records()
+            .collect::<core::result::Result<_, _>>()
+            .map_err(csv_error)?;
+        Ok(Lines { headers, records })
+    }
+
+    fn column(&self, name: &str) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name))
+    }
+
+    fn required(&self, name: &str) -> Result<usize> {
+        self.column(name)
+            .ok_or_else(|| Error::ParaError(format!("{}: missing column", name)))
+    }
+}
+
+fn line(rec: &StringRecord) -> u64 {
+    rec.position().map_or(0, |pos| pos.line())
+}
+
+/// A non-empty cell of column `col`, or `None` for a missing column or an empty cell
+fn cell(rec: &StringRecord, col: Option<usize>) -> Option<&str> {
+    col.and_then(|i| rec.get(i)).filter(|s| !s.is_empty())
+}
+
+fn required_cell<'r>(rec: &'r StringRecord, col: usize, name: &str) -> Result<&'r str> {
+    cell(rec, Some(col)).ok_or_else(|| cell_error(name, line(rec), "empty"))
+}
+
+fn parse_f64(s: &str, name: &str, rec: &StringRecord) -> Result<f64> {
+    match s.parse::<f64>() {
+        Ok(v) if v.is_finite() => Ok(v),
+        _ => Err(cell_error(name, line(rec), format!("not a number: {}", s))),
+    }
+}
+
+fn parse_u32(s: &str, name: &str, rec: &StringRecord) -> Result<u32> {
+    match parse_f64(s, name, rec)? {
+        n if n >= 0.0 && n.fract() == 0.0 && n <= u32::MAX as f64 => Ok(n as u32),
+        _ => Err(cell_error(
+            name,
+            line(rec),
+            format!("not a whole number: {}", s),
+        )),
+    }
+}
+
+fn parse_when(s: &str, name: &str, rec: &StringRecord) -> Result<WhenType> {
+    s.parse()
+        .map_err(|_| cell_error(name, line(rec), format!("invalid `when`: {}", s)))
+}
+
+fn check_period(per: u32, name: &str, rec: &StringRecord) -> Result<u32> {
+    if per > MAX_PERIOD {
+        return Err(cell_error(
+            name,
+            line(rec),
+            format!("beyond the last period {}: {}", MAX_PERIOD, per),
+        ));
+    }
+    Ok(per)
+}
+
+/// Read a cash flow, see the [module](self) documentation; `frequency` only matters for a `date` column
+pub fn read_cash_flows<R: io::Read>(rdr: R, frequency: Frequency) -> Result<CashFlows> {
+    let lines = Lines::read(rdr)?;
+    let amount = lines.required("amount")?;
+    let amounts = lines
+        .records
+        .iter()
+        .map(|rec| parse_f64(required_cell(rec, amount, "amount")?, "amount", rec))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(period) = lines.column("period") {
+        let periods = lines
+            .records
+            .iter()
+            .map(|rec| {
+                let per = parse_u32(required_cell(rec, period, "period")?, "period", rec)?;
+                check_period(per, "period", rec)
+            })
+            .collect::<Result<_>>()?;
+        return Ok(CashFlows { periods, amounts });
+    }
+
+    let date = lines
+        .column("date")
+        .ok_or_else(|| Error::ParaError("period or date: missing column".to_string()))?;
+    let dates = lines
+        .records
+        .iter()
+        .map(|rec| {
+            let s = required_cell(rec, date, "date")?;
+            s.parse::<Date>()
+                .map_err(|_| cell_error("date", line(rec), format!("not a date: {}", s)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let Some(start) = dates.iter().min().copied() else {
+        return Ok(CashFlows::default());
+    };
+    let periods = dates
+        .iter()
+        .zip(lines.records.iter())
+        .map(|(d, rec)| {
+            let months = d.months_since(&start);
+            // the same day of the month, or the end of a shorter month
+            let same_day = d.day() == start.day()
+                || (d.is_month_end() && (start.is_month_end() || d.day() < start.day()));
+            if !same_day || months % frequency.months() != 0 {
+                return Err(cell_error(
+                    "date",
+                    line(rec),
+                    format!("{} is not a whole number of periods after {}", d, start),
+                ));
+            }
+            let per = u32::try_from(months / frequency.months())
+                .map_err(|_| cell_error("date", line(rec), "too far in the future"))?;
+            check_period(per, "date", rec)
+        })
+        .collect::<Result<_>>()?;
+    Ok(CashFlows { periods, amounts })
+}
+
+/// Read a loan tape, see the [module](self) documentation
+pub fn read_loans<R: io::Read>(rdr: R) -> Result<Vec<Loan>> {
+    let lines = Lines::read(rdr)?;
+    let (rate, nper, pv) = (
+        lines.required("rate")?,
+        lines.required("nper")?,
+        lines.required("pv")?,
+    );
+    let (fv, when) = (lines.column("fv"), lines.column("when"));
+    lines
+        .records
+        .iter()
+        .map(|rec| {
+            Ok(Loan {
+                rate: parse_f64(required_cell(rec, rate, "rate")?, "rate", rec)?,
+                nper: parse_u32(required_cell(rec, nper, "nper")?, "nper", rec)?,
+                pv: parse_f64(required_cell(rec, pv, "pv")?, "pv", rec)?,
+                fv: cell(rec, fv).map_or(Ok(0.0), |s| parse_f64(s, "fv", rec))?,
+                when: cell(rec, when).map_or(Ok(WhenType::End), |s| parse_when(s, "when", rec))?,
+            })
+        })
+        .collect()
+}
+
+/// Read a loan tape as a `ParaMap` for each loan, e.g. for `Calculator::batch_params`
+pub fn read_loan_maps<R: io::Read>(rdr: R) -> Result<Vec<ParaMap>> {
+    Ok(read_loans(rdr)?.iter().map(Loan::to_map).collect())
+}
+
+/// Write the loans of a tape with their payment in a column `pmt`
+pub fn write_loans<W: io::Write>(wtr: W, loans: &[Loan]) -> Result<()> {
+    let mut wtr = Writer::from_writer(wtr);
+    wtr.write_record(["rate", "nper", "pv", "fv", "when", "pmt"])
+        .map_err(csv_error)?;
+    for (row, loan) in loans.iter().enumerate() {
+        let pmt = loan
+            .payment()
+            .get()
+            .map_err(|err| Error::OtherError(format!("row {}: {}", row, err)))?;
+        let when = match loan.when {
+            WhenType::End => "end".to_string(),
+            WhenType::Begin => "begin".to_string(),
+            WhenType::Middle => "middle".to_string(),
+            WhenType::Offset(_) => loan.when.offset().to_string(),
+        };
+        wtr.write_record([
+            loan.rate.to_string(),
+            loan.nper.to_string(),
+            loan.pv.to_string(),
+            loan.fv.to_string(),
+            when,
+            pmt.to_string(),
+        ])
+        .map_err(csv_error)?;
+    }
+    wtr.flush()
+        .map_err(|err| Error::OtherError(err.to_string()))
+}
+
+/// Write an amortization schedule, e.g. of an `AdjustableRateLoan`, one period per line
+pub fn write_schedule<W: io::Write>(wtr: W, rows: &[AmortizationRow]) -> Result<()> {
+    let mut wtr = Writer::from_writer(wtr);
+    wtr.write_record(["per", "rate", "pmt", "ipmt", "ppmt", "balance"])
+        .map_err(csv_error)?;
+    for row in rows {
+        wtr.write_record([
+            row.per.to_string(),
+            row.rate.to_string(),
+            row.pmt.to_string(),
+            row.ipmt.to_string(),
+            row.ppmt.to_string(),
+            row.balance.to_string(),
+        ])
+        .map_err(csv_error)?;
+    }
+    wtr.flush()
+        .map_err(|err| Error::OtherError(err.to_string()))
+}
+
+/// Write a [`Table`], e.g. an npv profile, with missing cells left empty as by `Table::to_csv`
+pub fn write_table<W: io::Write>(mut wtr: W, table: &Table) -> Result<()> {
+    wtr.write_all(table.to_csv().as_bytes())
+        .map_err(|err| Error::OtherError(err.to_string()))
+}
+
+#[allow(unused_imports)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn test_csv_cash_flows() {
+        // periods in any order, with two payments in period 1 and none in period 2
+        let flows = "amount,period\n-1000,0\n200,1\n100, 1\n800,3\n";
+        let flows = read_cash_flows(flows.as_bytes(), Frequency::Monthly).unwrap();
+        assert_eq!(flows.periods, vec![0, 1, 1, 3]);
+        assert_eq!(flows.values(), vec![-1000.0, 300.0, 0.0, 800.0]);
+        let tgt = NetPresentValue::from_tuple((vec![-1000.0, 300.0, 0.0, 800.0], 0.05))
+            .get()
+            .unwrap();
+        assert_eq!(flows.npv(0.05).get().unwrap(), tgt);
+        let mut map = flows.to_map();
+        map.insert("rate".to_string(), ParaType::F64(0.05));
+        assert_eq!(NetPresentValue::from_map(map).unwrap().get().unwrap(), tgt);
+
+        // quarterly dates from a month end, not in order
+        let flows = "date,amount\n2024-08-31,800\n2024-02-29,-1000\n2024-05-31,300\n";
+        let flows = read_cash_flows(flows.as_bytes(), Frequency::Quarterly).unwrap();
+        assert_eq!(flows.values(), vec![-1000.0, 300.0, 800.0]);
+        let tgt = InternalRateReturn::from_vec(vec![-1000.0, 300.0, 800.0])
+            .get()
+            .unwrap();
+        assert_eq!(flows.irr().get().unwrap(), tgt);
+
+        let flows = read_cash_flows("date,amount\n".as_bytes(), Frequency::Annual).unwrap();
+        assert_eq!(flows, CashFlows::default());
+    }
+
+    #[test]
+    fn test_csv_cash_flows_errors() {
+        let err = |s: &str, freq| match read_cash_flows(s.as_bytes(), freq) {
+            Err(Error::ParaError(msg)) => msg,
+            res => panic!("{:?}", res),
+        };
+        assert_eq!(
+            err("period,amount\n0,-100\n1,abc\n", Frequency::Monthly),
+            "amount: line 3: not a number: abc"
+        );
+        assert_eq!(
+            err("period,amount\n0,-100\n1.5,10\n", Frequency::Monthly),
+            "period: line 3: not a whole number: 1.5"
+        );
+        assert_eq!(
+            err("period,amount\n0,-100\n,10\n", Frequency::Monthly),
+            "period: line 3: empty"
+        );
+        assert_eq!(
+            err("period,amount\n0,-100\n4000000000,10\n", Frequency::Monthly),
+            "period: line 3: beyond the last period 100000: 4000000000"
+        );
+        assert_eq!(
+            err(
+                "date,amount\n0001-01-31,-100\n9999-12-31,10\n",
+                Frequency::Monthly
+            ),
+            "date: line 3: beyond the last period 100000: 119987"
+        );
+        assert_eq!(
+            err(
+                "date,amount\n2024-01-01,-100\n2024-02-30,10\n",
+                Frequency::Monthly
+            ),
+            "date: line 3: not a date: 2024-02-30"
+        );
+        assert_eq!(
+            err(
+                "date,amount\n2024-01-15,-100\n2024-03-15,10\n",
+                Frequency::Quarterly
+            ),
+            "date: line 3: 2024-03-15 is not a whole number of periods after 2024-01-15"
+        );
+        assert_eq!(
+            err("amount\n-100\n", Frequency::Monthly),
+            "period or date: missing column"
+        );
+        assert_eq!(
+            err("period\n0\n", Frequency::Monthly),
+            "amount: missing column"
+        );
+        // a line with a missing cell
+        let msg = err("period,amount\n0,-100\n1\n", Frequency::Monthly);
+        assert!(msg.contains("line: 3"), "{}", msg);
+    }
+
+    #[test]
+    fn test_csv_loans() {
+        let tape = "id,rate,nper,pv,fv,when\n\
+                    a,0.004,360,250000,,end\n\
+                    b,0.01,24,2000,100,1\n\
+                    c,0.01,24.0,2000,0,Begin\n";
+        let loans = read_loans(tape.as_bytes()).unwrap();
+        assert_eq!(loans.len(), 3);
+        assert_eq!(
+            loans[1],
+            Loan {
+                rate: 0.01,
+                nper: 24,
+                pv: 2000.0,
+                fv: 100.0,
+                when: WhenType::Begin
+            }
+        );
+        assert_eq!(loans[0].fv, 0.0);
+        let tgt = Payment::from_tuple((0.004, 360, 250000.0, 0.0, WhenType::End));
+        assert_eq!(loans[0].payment(), tgt);
+
+        let maps = read_loan_maps(tape.as_bytes()).unwrap();
+        let res = Payment::from_map(maps[0].clone()).unwrap();
+        assert_eq!(res, tgt);
+
+        let mut out = Vec::new();
+        write_loans(&mut out, &loans[..1]).unwrap();
+        let tgt = format!(
+            "rate,nper,pv,fv,when,pmt\n0.004,360,250000,0,end,{}\n",
+            tgt.get().unwrap()
+        );
+        assert_eq!(String::from_utf8(out).unwrap(), tgt);
+
+        let err = |s: &str| match read_loans(s.as_bytes()) {
+            Err(Error::ParaError(msg)) => msg,
+            res => panic!("{:?}", res),
+        };
+        assert_eq!(
+            err("rate,nper,pv,when\n0.01,12,100,1.5\n"),
+            "when: line 2: invalid `when`: 1.5"
+        );
+        assert_eq!(
+            err("rate,nper,pv\n0.01,-12,100\n"),
+            "nper: line 2: not a whole number: -12"
+        );
+        assert_eq!(err("rate,pv\n0.01,100\n"), "nper: missing column");
+    }
+
+    #[test]
+    fn test_csv_write() {
+        let rows = vec![AmortizationRow {
+            per: 1,
+            rate: 0.01,
+            pmt: 10.5,
+            ipmt: 1.0,
+            ppmt: 9.5,
+            balance: 90.5,
+        }];
+        let mut out = Vec::new();
+        write_schedule(&mut out, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "per,rate,pmt,ipmt,ppmt,balance\n1,0.01,10.5,1,9.5,90.5\n"
+        );
+
+        let table = Table {
+            columns: vec!["rate".to_string(), "npv".to_string()],
+            rows: vec![vec![0.05, f64::NAN]],
+        };
+        let mut out = Vec::new();
+        write_table(&mut out, &table).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), table.to_csv());
+    }
+}
@@ -1,24 +1,30 @@
 use core::fmt;
 
 // Customized Result
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+// Message of an error, static without `alloc`
+#[cfg(feature = "alloc")]
+type Message = alloc::string::String;
+#[cfg(not(feature = "alloc"))]
+type Message = &'static str;
 
 // Customized Error
 #[derive(Debug)]
 pub enum Error {
-    ParaError(String),
-    ConstructorError(String),
-    OtherError(String),
+    ParaError(Message),
+    ConstructorError(Message),
+    OtherError(Message),
 }
 
 // Parameter Error
 #[derive(Debug)]
 pub struct ParaError;
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> core::fmt::Result {
         write!(fmt, "{:?}", self)
     }
 }
 
-impl std::error::Error for Error {}
+impl core::error::Error for Error {}
@@ -0,0 +1,354 @@
+//! # Spreadsheet-compatible functions
+//! Wrappers over the calculators of this crate with the names, argument order and defaults of Excel, e.g.
+//! `PMT(rate, nper, pv, [fv], [type])`. Optional arguments are `Option`s and errors are Excel's error values.
+//!
+//! Differences from `numpy_financial`:
+//! * [`npv`] discounts the first value by one period, i.e. all values are at the end of periods
+//! * `type` is a number where `0` means the end and any other value the begining of periods
+//!
+//! ## Example
+//! ```rust
+//! use rfinancial::excel;
+//! let pmt = excel::pmt(0.08 / 12.0, 10.0, 10000.0, None, None);
+//! println!("PMT is {:?}", pmt);
+//! ```
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::{
+    compare::npv_roots, Error, InterestPayment, ModifiedIRR, NetPresentValue, NumberPeriod,
+    PrincipalPayment, Rate, WhenType,
+};
+
+/// Error values of a spreadsheet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExcelError {
+    /// `#NUM!`: no solution or an invalid number, e.g. a fractional `per`
+    Num,
+    /// `#VALUE!`: an argument of the wrong type or a formula that cannot be parsed
+    Value,
+    /// `#NAME?`: an unknown function or variable
+    Name,
+    /// `#DIV/0!`: a division by zero
+    Div0,
+}
+
+impl core::fmt::Display for ExcelError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = match self {
+            ExcelError::Num => "#NUM!",
+            ExcelError::Value => "#VALUE!",
+            ExcelError::Name => "#NAME?",
+            ExcelError::Div0 => "#DIV/0!",
+        };
+        write!(fmt, "{}", s)
+    }
+}
+
+impl core::error::Error for ExcelError {}
+
+impl From<Error> for ExcelError {
+    fn from(_: Error) -> Self {
+        ExcelError::Value
+    }
+}
+
+/// Result of a spreadsheet function
+pub type ExcelResult<T> = core::result::Result<T, ExcelError>;
+
+/// `type` argument as [`WhenType`]
+fn when(typ: Option<f64>) -> WhenType {
+    match typ {
+        Some(t) if t != 0.0 => WhenType::Begin,
+        _ => WhenType::End,
+    }
+}
+
+/// Periods of `IPMT` and `PPMT` must be whole numbers to be passed to the calculators
+fn whole(n: f64) -> ExcelResult<u32> {
+    if n.is_finite() && n >= 0.0 && n.fract() == 0.0 && n <= u32::MAX as f64 {
+        Ok(n as u32)
+    } else {
+        Err(ExcelError::Num)
+    }
+}
+
+/// `(1+rate)**nper` and the value at `nper` of a payment in each period, i.e. `(1+rate*type)*((1+rate)**nper-1)/rate`
+/// * `nper` may be fractional as in spreadsheets and `numpy_financial`, so these are not computed by the calculators
+///   taking a `u32`
+pub(crate) fn growth(rate: f64, nper: f64, when: WhenType) -> (f64, f64) {
+    if rate == 0.0 {
+        (1.0, nper)
+    } else {
+        let t = (1.0 + rate).powf(nper);
+        (t, when.growth(rate) * (t - 1.0) / rate)
+    }
+}
+
+/// Spreadsheets show `#NUM!` rather than `NaN` or infinity
+fn finite(v: f64) -> ExcelResult<f64> {
+    if v.is_finite() {
+        Ok(v)
+    } else {
+        Err(ExcelError::Num)
+    }
+}
+
+/// `PMT(rate, nper, pv, [fv], [type])`
+pub fn pmt(rate: f64, nper: f64, pv: f64, fv: Option<f64>, typ: Option<f64>) -> ExcelResult<f64> {
+    let (t, a) = growth(rate, nper, when(typ));
+    finite(-(fv.unwrap_or(0.0) + pv * t) / a)
+}
+
+/// `IPMT(rate, per, nper, pv, [fv], [type])`
+pub fn ipmt(
+    rate: f64,
+    per: f64,
+    nper: f64,
+    pv: f64,
+    fv: Option<f64>,
+    typ: Option<f64>,
+) -> ExcelResult<f64> {
+    let (per, nper) = (whole(per)?, whole(nper)?);
+    if per < 1 || per > nper {
+        return Err(ExcelError::Num);
+    }
+    let tup = (rate, per, nper, pv, fv.unwrap_or(0.0), when(typ));
+    finite(
+        InterestPayment::from_tuple(tup)
+            .get()?
+            .ok_or(ExcelError::Num)?,
+    )
+}
+
+/// `PPMT(rate, per, nper, pv, [fv], [type])`
+pub fn ppmt(
+    rate: f64,
+    per: f64,
+    nper: f64,
+    pv: f64,
+    fv: Option<f64>,
+    typ: Option<f64>,
+) -> ExcelResult<f64> {
+    let (per, nper) = (whole(per)?, whole(nper)?);
+    if per < 1 || per > nper {
+        return Err(ExcelError::Num);
+    }
+    let tup = (rate, per, nper, pv, fv.unwrap_or(0.0), when(typ));
+    finite(
+        PrincipalPayment::from_tuple(tup)
+            .get()?
+            .ok_or(ExcelError::Num)?,
+    )
+}
+
+/// `NPER(rate, pmt, pv, [fv], [type])`
+pub fn nper(rate: f64, pmt: f64, pv: f64, fv: Option<f64>, typ: Option<f64>) -> ExcelResult<f64> {
+    let tup = (rate, pmt, pv, fv.unwrap_or(0.0), when(typ));
+    finite(
+        NumberPeriod::from_tuple(tup)
+            .get()?
+            .ok_or(ExcelError::Num)?,
+    )
+}
+
+/// `RATE(nper, pmt, pv, [fv], [type], [guess])`
+pub fn rate(
+    nper: f64,
+    pmt: f64,
+    pv: f64,
+    fv: Option<f64>,
+    typ: Option<f64>,
+    guess: Option<f64>,
+) -> ExcelResult<f64> {
+    let (fv, when) = (fv.unwrap_or(0.0), when(typ));
+    // Newton's method of `Rate` with a fractional `nper`
+    let mut rn = guess.unwrap_or(0.1);
+    for _ in 0..100 {
+        let rnp1 = rn - Rate::_g_div_gp(rn, nper, pmt, pv, fv, when.clone());
+        if (rnp1 - rn).abs() < 1e-10 {
+            return finite(rnp1);
+        }
+        rn = rnp1;
+    }
+    Err(ExcelError::Num)
+}
+
+/// `PV(rate, nper, pmt, [fv], [type])`
+pub fn pv(rate: f64, nper: f64, pmt: f64, fv: Option<f64>, typ: Option<f64>) -> ExcelResult<f64> {
+    let (t, a) = growth(rate, nper, when(typ));
+    finite(-(fv.unwrap_or(0.0) + pmt * a) / t)
+}
+
+/// `FV(rate, nper, pmt, [pv], [type])`
+pub fn fv(rate: f64, nper: f64, pmt: f64, pv: Option<f64>, typ: Option<f64>) -> ExcelResult<f64> {
+    let (t, a) = growth(rate, nper, when(typ));
+    finite(-(pv.unwrap_or(0.0) * t + pmt * a))
+}
+
+/// `NPV(rate, value1, [value2], ...)`, where the first value is discounted by one period
+pub fn npv(rate: f64, values: &[f64]) -> ExcelResult<f64> {
+    if rate == -1.0 {
+        return Err(ExcelError::Div0);
+    }
+    let npv = NetPresentValue::from_slice((values, rate)).get()?;
+    finite(npv / (1.0 + rate))
+}
+
+/// `IRR(values, [guess])`, i.e. the rate nearest to `guess` where the npv of `values` is 0
+pub fn irr(values: &[f64], guess: Option<f64>) -> ExcelResult<f64> {
+    let any_negative = values.iter().any(|&v| v < 0.0);
+    let any_positive = values.iter().any(|&v| v > 0.0);
+    if !(any_negative && any_positive) {
+        return Err(ExcelError::Num);
+    }
+    let guess = guess.unwrap_or(0.1);
+    let irr = npv_roots(values)?
+        .into_iter()
+        .min_by(|a, b| (a - guess).abs().total_cmp(&(b - guess).abs()));
+    finite(irr.ok_or(ExcelError::Num)?)
+}
+
+/// `MIRR(values, finance_rate, reinvest_rate)`
+pub fn mirr(values: &[f64], finance_rate: f64, reinvest_rate: f64) -> ExcelResult<f64> {
+    let any_negative = values.iter().any(|&v| v < 0.0);
+    let any_positive = values.iter().any(|&v| v > 0.0);
+    if !(any_negative && any_positive) {
+        return Err(ExcelError::Div0);
+    }
+    let mirr = ModifiedIRR::from_slice((values, finance_rate, reinvest_rate)).get()?;
+    finite(mirr.ok_or(ExcelError::Div0)?)
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    // Excel displays results rounded, so compare within half of the last displayed digit
+    fn check(res: ExcelResult<f64>, tgt: f64, tol: f64) {
+        let res = res.unwrap();
+        assert!((res - tgt).abs() <= tol, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_excel_tvm() {
+        // PMT(8%/12, 10, 10000) -> -1,037.03
+        check(pmt(0.08 / 12.0, 10.0, 10000.0, None, None), -1037.03, 0.005);
+        // PMT(8%/12, 10, 10000, 0, 1) -> -1,030.16
+        check(
+            pmt(0.08 / 12.0, 10.0, 10000.0, Some(0.0), Some(1.0)),
+            -1030.16,
+            0.005,
+        );
+        // IPMT(10%/12, 1, 3*12, 8000) -> -66.67
+        check(
+            ipmt(0.1 / 12.0, 1.0, 36.0, 8000.0, None, None),
+            -66.67,
+            0.005,
+        );
+        // IPMT(10%, 3, 3, 8000) -> -292.45
+        check(ipmt(0.1, 3.0, 3.0, 8000.0, None, None), -292.45, 0.005);
+        // PPMT(10%/12, 1, 2*12, 2000) -> -75.62
+        check(
+            ppmt(0.1 / 12.0, 1.0, 24.0, 2000.0, None, None),
+            -75.62,
+            0.005,
+        );
+        // PPMT(8%, 10, 10, 200000) -> -27,598.05
+        check(
+            ppmt(0.08, 10.0, 10.0, 200000.0, None, None),
+            -27598.05,
+            0.005,
+        );
+        // NPER(12%/12, -100, -1000, 10000, 1) -> 59.6738657
+        check(
+            nper(0.01, -100.0, -1000.0, Some(10000.0), Some(1.0)),
+            59.6738657,
+            5e-8,
+        );
+        // NPER(12%/12, -100, -1000, 10000) -> 60.0821229
+        check(
+            nper(0.01, -100.0, -1000.0, Some(10000.0), None),
+            60.0821229,
+            5e-8,
+        );
+        // NPER(12%/12, -100, -1000) -> -9.57859404
+        check(nper(0.01, -100.0, -1000.0, None, None), -9.57859404, 5e-9);
+        // RATE(4*12, -200, 8000) -> 0.77%
+        check(rate(48.0, -200.0, 8000.0, None, None, None), 0.0077, 5e-5);
+        // PV(0.08/12, 12*20, 500, , 0) -> -59,777.15
+        check(
+            pv(0.08 / 12.0, 240.0, 500.0, None, Some(0.0)),
+            -59777.15,
+            0.005,
+        );
+        // FV(0.06/12, 10, -200, -500, 1) -> 2,581.40
+        check(
+            fv(0.06 / 12.0, 10.0, -200.0, Some(-500.0), Some(1.0)),
+            2581.40,
+            0.005,
+        );
+        // FV(0.12/12, 12, -1000) -> 12,682.50
+        check(fv(0.01, 12.0, -1000.0, None, None), 12682.50, 0.005);
+        // PMT(0.01, 10.5, 1000) -> -100.80
+        check(pmt(0.01, 10.5, 1000.0, None, None), -100.80, 0.005);
+        // PV(0.01, 10.5, -100.80) -> 999.99
+        check(pv(0.01, 10.5, -100.80, None, None), 999.99, 0.005);
+        // FV(0.01, 10.5, -100) -> 1,101.31
+        check(fv(0.01, 10.5, -100.0, None, None), 1101.31, 0.005);
+        // RATE(10.5, -100.80, 1000) -> 1.00%
+        check(rate(10.5, -100.80, 1000.0, None, None, None), 0.0100, 5e-5);
+        // FV(0.11/12, 35, -2000, , 1) -> 82,846.25
+        check(
+            fv(0.11 / 12.0, 35.0, -2000.0, None, Some(1.0)),
+            82846.25,
+            0.005,
+        );
+    }
+
+    #[test]
+    fn test_excel_cash_flow() {
+        // NPV(10%, -10000, 3000, 4200, 6800) -> 1,188.44
+        check(
+            npv(0.1, &[-10000.0, 3000.0, 4200.0, 6800.0]),
+            1188.44,
+            0.005,
+        );
+        // NPV(8%, 8000, 9200, 10000, 12000, 14500) + -40000 -> 1,922.06
+        let res = npv(0.08, &[8000.0, 9200.0, 10000.0, 12000.0, 14500.0]).map(|v| v - 40000.0);
+        check(res, 1922.06, 0.005);
+        // IRR(-70000, 12000, 15000, 18000, 21000) -> -2.1%
+        let values = [-70000.0, 12000.0, 15000.0, 18000.0, 21000.0];
+        check(irr(&values, None), -0.021, 5e-4);
+        // IRR(-70000, 12000, 15000, 18000, 21000, 26000) -> 8.7%
+        let values = [-70000.0, 12000.0, 15000.0, 18000.0, 21000.0, 26000.0];
+        check(irr(&values, None), 0.087, 5e-4);
+        // IRR(-70000, 12000, 15000, -10%) -> -44.4%
+        check(irr(&values[..3], Some(-0.1)), -0.444, 5e-4);
+        // MIRR(-120000, 39000, 30000, 21000, 37000, 46000, 10%, 12%) -> 12.6%
+        let values = [-120000.0, 39000.0, 30000.0, 21000.0, 37000.0, 46000.0];
+        check(mirr(&values, 0.1, 0.12), 0.126, 5e-4);
+        // MIRR(-120000, 39000, 30000, 21000, 10%, 12%) -> -4.8%
+        check(mirr(&values[..4], 0.1, 0.12), -0.048, 5e-4);
+        // MIRR(-120000, 39000, 30000, 21000, 37000, 46000, 10%, 14%) -> 13.5%
+        check(mirr(&values, 0.1, 0.14), 0.135, 5e-4);
+    }
+
+    #[test]
+    fn test_excel_errors() {
+        assert_eq!(
+            ipmt(0.01, 0.0, 10.0, 1000.0, None, None),
+            Err(ExcelError::Num)
+        );
+        assert_eq!(
+            ppmt(0.01, 11.0, 10.0, 1000.0, None, None),
+            Err(ExcelError::Num)
+        );
+        assert_eq!(pmt(0.0, 0.0, 1000.0, None, None), Err(ExcelError::Num));
+        assert_eq!(irr(&[100.0, 200.0], None), Err(ExcelError::Num));
+        assert_eq!(mirr(&[-100.0, -200.0], 0.1, 0.1), Err(ExcelError::Div0));
+        assert_eq!(npv(-1.0, &[100.0]), Err(ExcelError::Div0));
+        assert_eq!(ExcelError::Num.to_string(), "#NUM!");
+        assert_eq!(ExcelError::Div0.to_string(), "#DIV/0!");
+    }
+}
@@ -0,0 +1,520 @@
+//! # Spreadsheet formula evaluator
+//! Evaluate formulas such as `=PMT(0.05/12, 360, -250000)` or `=NPV(0.08, -1000, 300, 400, 500)` with the functions
+//! of [`crate::excel`]. It supports
+//! * numbers, including percentages such as `5%`
+//! * arithmetic with `+`, `-`, `*`, `/`, `^` and parentheses, following spreadsheet precedence, e.g. `-2^2` is `4`
+//! * array literals such as `{-1000, 300, 400}`
+//! * calls of `PMT`, `IPMT`, `PPMT`, `NPER`, `RATE`, `PV`, `FV`, `NPV`, `IRR` and `MIRR`, with optional arguments
+//!   left empty as in `PV(0.08/12, 240, 500, , 0)`
+//! * named variables, but no cell references
+//!
+//! Formulas nested deeper than 256 levels or with more than 1024 operators, calls and arrays are rejected with
+//! [`ExcelError::Value`].
+//!
+//! ## Example
+//! ```rust
+//! use rfinancial::formula::*;
+//! let pmt = Formula::parse("=PMT(0.05/12, 360, -250000)").unwrap();
+//! println!("PMT is {:?}", pmt.eval(&Variables::new()));
+//! ```
+use crate::excel::{self, ExcelError, ExcelResult};
+
+/// Value of a formula or of a named variable
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Array(Vec<f64>),
+}
+
+/// Named variables a formula can refer to, with case-insensitive names
+pub type Variables = std::collections::HashMap<String, Value>;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Number(f64),
+    Array(Vec<Expr>),
+    Variable(String),
+    Negate(Box<Expr>),
+    Percent(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    /// a call with its arguments, where an empty argument is `None`
+    Call(String, Vec<Option<Expr>>),
+}
+
+/// # A parsed spreadsheet formula
+#[derive(Clone, Debug, PartialEq)]
+pub struct Formula {
+    expr: Expr,
+}
+
+/// Maximum nesting of parentheses, arrays, calls and unary signs
+const MAX_DEPTH: usize = 256;
+/// Maximum number of operators, calls and arrays, which bounds the depth of evaluation
+const MAX_NODES: usize = 1024;
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    depth: usize,
+    nodes: usize,
+}
+
+impl Parser<'_> {
+    fn enter(&mut self) -> ExcelResult<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(ExcelError::Value);
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn node(&mut self) -> ExcelResult<()> {
+        self.nodes += 1;
+        if self.nodes > MAX_NODES {
+            return Err(ExcelError::Value);
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().copied()
+    }
+
+    fn expect(&mut self, c: char) -> ExcelResult<()> {
+        if self.peek() == Some(c) {
+            self.chars.next();
+            Ok(())
+        } else {
+            Err(ExcelError::Value)
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> ExcelResult<Expr> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.chars.next();
+            self.node()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    // term := power (('*' | '/') power)*
+    fn term(&mut self) -> ExcelResult<Expr> {
+        let mut lhs = self.power()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.chars.next();
+            self.node()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.power()?));
+        }
+        Ok(lhs)
+    }
+
+    // power := unary ('^' unary)*, left-associative as in spreadsheets
+    fn power(&mut self) -> ExcelResult<Expr> {
+        let mut lhs = self.unary()?;
+        while self.peek() == Some('^') {
+            self.chars.next();
+            self.node()?;
+            lhs = Expr::Binary('^', Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    // unary := ('-' | '+')* postfix, binding tighter than '^' as in spreadsheets
+    fn unary(&mut self) -> ExcelResult<Expr> {
+        let expr = match self.peek() {
+            Some('-') => {
+                self.chars.next();
+                self.enter()?;
+                self.node()?;
+                Expr::Negate(Box::new(self.unary()?))
+            }
+            Some('+') => {
+                self.chars.next();
+                self.enter()?;
+                self.unary()?
+            }
+            _ => return self.postfix(),
+        };
+        self.leave();
+        Ok(expr)
+    }
+
+    // postfix := primary '%'*
+    fn postfix(&mut self) -> ExcelResult<Expr> {
+        let mut expr = self.primary()?;
+        while self.peek() == Some('%') {
+            self.chars.next();
+            self.node()?;
+            expr = Expr::Percent(Box::new(expr));
+        }
+        Ok(expr)
+    }
+
+    // primary := number | '(' expr ')' | '{' expr (',' expr)* '}' | name | name '(' args ')'
+    fn primary(&mut self) -> ExcelResult<Expr> {
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some('(') => {
+                self.chars.next();
+                self.enter()?;
+                let expr = self.expr()?;
+                self.expect(')')?;
+                self.leave();
+                Ok(expr)
+            }
+            Some('{') => {
+                self.chars.next();
+                self.enter()?;
+                self.node()?;
+                let mut items = vec![self.expr()?];
+                // rows are separated by ';' and columns by ',', both are flattened
+                while let Some(',' | ';') = self.peek() {
+                    self.chars.next();
+                    items.push(self.expr()?);
+                }
+                self.expect('}')?;
+                self.leave();
+                Ok(Expr::Array(items))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(c) = self
+                    .chars
+                    .next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    name.push(c);
+                }
+                if self.peek() == Some('(') {
+                    self.chars.next();
+                    self.enter()?;
+                    self.node()?;
+                    let args = self.args()?;
+                    self.leave();
+                    Ok(Expr::Call(name.to_uppercase(), args))
+                } else {
+                    Ok(Expr::Variable(name.to_lowercase()))
+                }
+            }
+            _ => Err(ExcelError::Value),
+        }
+    }
+
+    // args := [expr] (',' [expr])* ')'
+    fn args(&mut self) -> ExcelResult<Vec<Option<Expr>>> {
+        let mut args = Vec::new();
+        if self.peek() == Some(')') {
+            self.chars.next();
+            return Ok(args);
+        }
+        loop {
+            match self.peek() {
+                Some(',') | Some(')') => args.push(None),
+                _ => args.push(Some(self.expr()?)),
+            }
+            match self.peek() {
+                Some(',') => {
+                    self.chars.next();
+                }
+                Some(')') => {
+                    self.chars.next();
+                    return Ok(args);
+                }
+                _ => return Err(ExcelError::Value),
+            }
+        }
+    }
+
+    fn number(&mut self) -> ExcelResult<Expr> {
+        let mut text = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            text.push(c);
+        }
+        // exponent, e.g. 1e-6
+        if let Some(e) = self.chars.next_if(|c| *c == 'e' || *c == 'E') {
+            text.push(e);
+            if let Some(sign) = self.chars.next_if(|c| *c == '+' || *c == '-') {
+                text.push(sign);
+            }
+            while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit()) {
+                text.push(c);
+            }
+        }
+        text.parse()
+            .map(Expr::Number)
+            .map_err(|_| ExcelError::Value)
+    }
+}
+
+fn number(value: Value) -> ExcelResult<f64> {
+    match value {
+        Value::Number(v) => Ok(v),
+        Value::Array(_) => Err(ExcelError::Value),
+    }
+}
+
+impl Expr {
+    fn eval(&self, vars: &Variables) -> ExcelResult<Value> {
+        let num = |e: &Expr| number(e.eval(vars)?);
+        let res = match self {
+            Expr::Number(v) => *v,
+            Expr::Array(items) => {
+                return Ok(Value::Array(
+                    items.iter().map(num).collect::<ExcelResult<_>>()?,
+                ))
+            }
+            Expr::Variable(name) => {
+                return vars
+                    .iter()
+                    .find(|(k, _)| k.to_lowercase() == *name)
+                    .map(|(_, v)| v.clone())
+                    .ok_or(ExcelError::Name)
+            }
+            Expr::Negate(e) => -num(e)?,
+            Expr::Percent(e) => num(e)? / 100.0,
+            Expr::Binary(op, lhs, rhs) => {
+                let (l, r) = (num(lhs)?, num(rhs)?);
+                match op {
+                    '+' => l + r,
+                    '-' => l - r,
+                    '*' => l * r,
+                    '/' if r == 0.0 => return Err(ExcelError::Div0),
+                    '/' => l / r,
+                    _ => l.powf(r),
+                }
+            }
+            Expr::Call(name, args) => call(name, args, vars)?,
+        };
+        if res.is_finite() {
+            Ok(Value::Number(res))
+        } else {
+            Err(ExcelError::Num)
+        }
+    }
+}
+
+fn call(name: &str, args: &[Option<Expr>], vars: &Variables) -> ExcelResult<f64> {
+    // optional argument, where an empty or missing one is `None`
+    let opt = |i: usize| -> ExcelResult<Option<f64>> {
+        match args.get(i) {
+            Some(Some(e)) => Ok(Some(number(e.eval(vars)?)?)),
+            _ => Ok(None),
+        }
+    };
+    let req = |i: usize| opt(i)?.ok_or(ExcelError::Value);
+    // all arguments from `i` on, with arrays flattened
+    let flat = |i: usize| -> ExcelResult<Vec<f64>> {
+        let mut values = Vec::new();
+        for arg in args.iter().skip(i) {
+            match arg.as_ref().map(|e| e.eval(vars)).transpose()? {
+                Some(Value::Number(v)) => values.push(v),
+                Some(Value::Array(v)) => values.extend(v),
+                None => return Err(ExcelError::Value),
+            }
+        }
+        Ok(values)
+    };
+    let arity = |min: usize, max: usize| {
+        if args.len() < min || args.len() > max {
+            Err(ExcelError::Value)
+        } else {
+            Ok(())
+        }
+    };
+
+    match name {
+        "PMT" => {
+            arity(3, 5)?;
+            excel::pmt(req(0)?, req(1)?, req(2)?, opt(3)?, opt(4)?)
+        }
+        "IPMT" => {
+            arity(4, 6)?;
+            excel::ipmt(req(0)?, req(1)?, req(2)?, req(3)?, opt(4)?, opt(5)?)
+        }
+        "PPMT" => {
+            arity(4, 6)?;
+            excel::ppmt(req(0)?, req(1)?, req(2)?, req(3)?, opt(4)?, opt(5)?)
+        }
+        "NPER" => {
+            arity(3, 5)?;
+            excel::nper(req(0)?, req(1)?, req(2)?, opt(3)?, opt(4)?)
+        }
+        "RATE" => {
+            arity(3, 6)?;
+            excel::rate(req(0)?, req(1)?, req(2)?, opt(3)?, opt(4)?, opt(5)?)
+        }
+        "PV" => {
+            arity(3, 5)?;
+            excel::pv(req(0)?, req(1)?, req(2)?, opt(3)?, opt(4)?)
+        }
+        "FV" => {
+            arity(3, 5)?;
+            excel::fv(req(0)?, req(1)?, req(2)?, opt(3)?, opt(4)?)
+        }
+        "NPV" => {
+            arity(2, usize::MAX)?;
+            excel::npv(req(0)?, &flat(1)?)
+        }
+        "IRR" => {
+            arity(1, 2)?;
+            let values = match args[0].as_ref().map(|e| e.eval(vars)).transpose()? {
+                Some(Value::Array(v)) => v,
+                _ => return Err(ExcelError::Value),
+            };
+            excel::irr(&values, opt(1)?)
+        }
+        "MIRR" => {
+            arity(3, 3)?;
+            let values = match args[0].as_ref().map(|e| e.eval(vars)).transpose()? {
+                Some(Value::Array(v)) => v,
+                _ => return Err(ExcelError::Value),
+            };
+            excel::mirr(&values, req(1)?, req(2)?)
+        }
+        _ => Err(ExcelError::Name),
+    }
+}
+
+impl Formula {
+    /// Parse a formula, with or without the leading `=`
+    pub fn parse(text: &str) -> ExcelResult<Self> {
+        let text = text.trim();
+        let text = text.strip_prefix('=').unwrap_or(text);
+        let mut parser = Parser {
+            chars: text.chars().peekable(),
+            depth: 0,
+            nodes: 0,
+        };
+        let expr = parser.expr()?;
+        if parser.peek().is_some() {
+            return Err(ExcelError::Value);
+        }
+        Ok(Formula { expr })
+    }
+
+    /// Evaluate the formula with named variables
+    pub fn eval(&self, vars: &Variables) -> ExcelResult<Value> {
+        self.expr.eval(vars)
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn eval(text: &str) -> ExcelResult<f64> {
+        number(Formula::parse(text)?.eval(&Variables::new())?)
+    }
+
+    #[test]
+    fn test_formula_arithmetic() {
+        assert_eq!(eval("=1 + 2 * 3"), Ok(7.0));
+        assert_eq!(eval("=(1 + 2) * 3"), Ok(9.0));
+        assert_eq!(eval("=-2^2"), Ok(4.0));
+        assert_eq!(eval("=2^3^2"), Ok(64.0));
+        assert_eq!(eval("=10 - 4 - 3"), Ok(3.0));
+        assert_eq!(eval("=5%"), Ok(0.05));
+        assert_eq!(eval("1e3 / 8"), Ok(125.0));
+    }
+
+    #[test]
+    fn test_formula_functions() {
+        let res = eval("=PMT(0.05/12, 360, -250000)").unwrap();
+        let tgt = excel::pmt(0.05 / 12.0, 360.0, -250000.0, None, None).unwrap();
+        assert_eq!(res, tgt);
+
+        let res = eval("=NPV(0.08, -1000, 300, 400, 500)").unwrap();
+        let tgt = excel::npv(0.08, &[-1000.0, 300.0, 400.0, 500.0]).unwrap();
+        assert_eq!(res, tgt);
+
+        // arrays are flattened into NPV
+        let res = eval("=NPV(8%, {-1000, 300}, 400, {500})").unwrap();
+        assert_eq!(res, tgt);
+
+        // PV(0.08/12, 12*20, 500, , 0) -> -59,777.15
+        let res = eval("=pv(0.08/12, 12*20, 500, , 0)").unwrap();
+        assert!((res + 59777.15).abs() < 0.005, "{}", res);
+
+        // IRR(-70000, 12000, 15000, 18000, 21000, 26000) -> 8.7%
+        let res = eval("=IRR({-70000, 12000, 15000, 18000, 21000, 26000})").unwrap();
+        assert!((res - 0.087).abs() < 5e-4, "{}", res);
+
+        // MIRR(-120000, 39000, 30000, 21000, 37000, 46000, 10%, 12%) -> 12.6%
+        let res = eval("=MIRR({-120000; 39000; 30000; 21000; 37000; 46000}, 10%, 12%)").unwrap();
+        assert!((res - 0.126).abs() < 5e-4, "{}", res);
+    }
+
+    #[test]
+    fn test_formula_variables() {
+        let mut vars = Variables::new();
+        vars.insert("Rate".into(), Value::Number(0.08));
+        vars.insert(
+            "flows".into(),
+            Value::Array(vec![-1000.0, 300.0, 400.0, 500.0]),
+        );
+        let formula = Formula::parse("=NPV(rate, flows) * 2").unwrap();
+        let res = number(formula.eval(&vars).unwrap()).unwrap();
+        let tgt = excel::npv(0.08, &[-1000.0, 300.0, 400.0, 500.0]).unwrap() * 2.0;
+        assert_eq!(res, tgt);
+
+        let formula = Formula::parse("={1, rate}").unwrap();
+        assert_eq!(formula.eval(&vars), Ok(Value::Array(vec![1.0, 0.08])));
+    }
+
+    #[test]
+    fn test_formula_errors() {
+        assert_eq!(eval("=1/0"), Err(ExcelError::Div0));
+        assert_eq!(eval("=XNPV(0.1, {1, 2})"), Err(ExcelError::Name));
+        assert_eq!(eval("=rate + 1"), Err(ExcelError::Name));
+        assert_eq!(eval("=PMT(0.05)"), Err(ExcelError::Value));
+        assert_eq!(eval("=1 +"), Err(ExcelError::Value));
+        assert_eq!(eval("=(1 + 2"), Err(ExcelError::Value));
+        assert_eq!(eval("=1 2"), Err(ExcelError::Value));
+        assert_eq!(eval("={1, 2} + 1"), Err(ExcelError::Value));
+        assert_eq!(eval("=IRR({100, 200})"), Err(ExcelError::Num));
+    }
+
+    #[test]
+    fn test_formula_limits() {
+        // nesting within the limit
+        let text = format!("={}1{}", "(".repeat(200), ")".repeat(200));
+        assert_eq!(eval(&text), Ok(1.0));
+        assert_eq!(eval(&format!("={}1", "-".repeat(200))), Ok(1.0));
+        let text = format!("=1{}", "+1".repeat(1000));
+        assert_eq!(eval(&text), Ok(1001.0));
+
+        // deep nesting
+        let text = format!("={}1{}", "(".repeat(2000), ")".repeat(2000));
+        assert_eq!(eval(&text), Err(ExcelError::Value));
+        assert_eq!(
+            eval(&format!("={}1", "-".repeat(100_000))),
+            Err(ExcelError::Value)
+        );
+        assert_eq!(
+            eval(&format!("={}1", "+".repeat(100_000))),
+            Err(ExcelError::Value)
+        );
+        let text = format!("={}1{}", "{".repeat(2000), "}".repeat(2000));
+        assert_eq!(eval(&text), Err(ExcelError::Value));
+
+        // a long chain
+        let text = format!("=1{}", "+1".repeat(1_000_000));
+        assert_eq!(eval(&text), Err(ExcelError::Value));
+        assert_eq!(
+            eval(&format!("=1{}", "%".repeat(100_000))),
+            Err(ExcelError::Value)
+        );
+    }
+}
@@ -1,6 +1,10 @@
-use crate::{get_f64, get_u32, get_when, Error, ParaMap, Result, WhenType};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_u32, get_when, Error, ParaMap};
+use crate::{Result, WhenType};
 /// # Compute the future value
-
+///
 /// ## Parameters
 /// * `rate` : an interest rate compounded once per period
 /// * `nper` : number of compounding periods
@@ -18,7 +22,7 @@ use crate::{get_f64, get_u32, get_when, Error, ParaMap, Result, WhenType};
 /// println!("{:#?}'s fv is {:?}", fv, fv.get());
 /// ```
 ///
-#[derive(Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct FutureValue {
     rate: f64,
     nper: u32,
@@ -43,6 +47,7 @@ impl FutureValue {
 
     /// Instantiate a `FutureValue` instance from a hash map with keys of (`rate`, `nper`, `pmt`, `pv` and `when`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
@@ -51,11 +56,11 @@ impl FutureValue {
             ))
         };
 
-        let rate = get_f64(&map, "rate").map_err(|err| op(err))?;
-        let nper = get_u32(&map, "nper").map_err(|err| op(err))?;
-        let pmt = get_f64(&map, "pmt").map_err(|err| op(err))?;
-        let pv = get_f64(&map, "pv").map_err(|err| op(err))?;
-        let when = get_when(&map, "when").map_err(|err| op(err))?;
+        let rate = get_f64(&map, "rate").map_err(&op)?;
+        let nper = get_u32(&map, "nper").map_err(&op)?;
+        let pmt = get_f64(&map, "pmt").map_err(&op)?;
+        let pv = get_f64(&map, "pv").map_err(&op)?;
+        let when = get_when(&map, "when").map_err(op)?;
 
         Ok(FutureValue {
             rate,
@@ -70,15 +75,14 @@ impl FutureValue {
     fn fv(&self) -> Result<f64> {
         /*
         Solve below equation if rate is not 0
-        fv + pv*(1+rate)**nper + pmt*(1+rate*when)/rate*((1+rate)**nper-1) = 0
+        fv + pv*(1+rate)**nper + pmt*(1+rate)**offset/rate*((1+rate)**nper-1) = 0, with `offset` of `when`
         but if rate is 0 then
         fv + pv + pmt*nper = 0
         */
         if self.rate != 0.0 {
             let tmp = (1.0 + self.rate).powf(self.nper as f64);
             let pv_future = self.pv * tmp;
-            let when_f64 = self.when.clone() as u8 as f64;
-            let pmt_future = self.pmt * (1.0 + self.rate * when_f64) / self.rate * (tmp - 1.0);
+            let pmt_future = self.pmt * self.when.growth(self.rate) / self.rate * (tmp - 1.0);
 
             Ok(-pv_future - pmt_future)
         } else {
@@ -122,6 +126,7 @@ mod tests {
         assert!(cond);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_fv_from_map() {
         let mut map = ParaMap::new();
@@ -219,6 +224,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_fv_nan() {
         let mut map = ParaMap::new();
@@ -234,6 +240,7 @@ mod tests {
         assert!(cond);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_fv_err() {
         let mut map = ParaMap::new();
@@ -247,4 +254,36 @@ mod tests {
 
         assert!(cond);
     }
+
+    #[test]
+    fn test_fv_with_middle() {
+        // payments in the middle of the periods grow by (1 + rate)^0.5 within their periods
+        // >>> -(-2000 * 1.075**0.5 * (1.075**20 - 1) / 0.075)
+        // 89798.49848593227
+        let fv = FutureValue::from_tuple((0.075, 20, -2000.0, 0.0, WhenType::Middle));
+        let res = fv.get().unwrap();
+        let tgt = 89798.49848593227;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        // the same as an offset of a half, and between the end and the begining
+        let fv = |when| {
+            FutureValue::from_tuple((0.075, 20, -2000.0, 0.0, when))
+                .get()
+                .unwrap()
+        };
+        assert_eq!(WhenType::from_offset(0.5), Some(WhenType::Middle));
+        let half = fv(WhenType::from_offset(0.5 + 1e-12).unwrap());
+        assert!(float_close(half, res, RTOL, ATOL), "{} v.s. {}", half, res);
+        assert!(fv(WhenType::End) < res && res < fv(WhenType::Begin));
+
+        // an offset out of `[0, 1]`
+        for offset in [-0.5, 2.0, f64::NAN] {
+            assert_eq!(WhenType::from_offset(offset), None, "{}", offset);
+        }
+    }
 }
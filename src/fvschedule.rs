@@ -0,0 +1,159 @@
+#[cfg(feature = "std")]
+use crate::{get_f64, get_vecf64, get_when, Error, ParaMap};
+use crate::{Result, WhenType};
+use alloc::vec::Vec;
+/// # Compute the future value through a schedule of per-period rates
+/// This is the variant of [`crate::FutureValue`] where each period has its own rate, similar to `FVSCHEDULE` in spreadsheets
+///
+/// ## Parameters
+/// * `rates` : an interest rate for each compounding period, so the number of periods is `rates.len()`
+/// * `pmt` : payment in each period
+/// * `pv` : present value
+/// * `when` : when payments are due [`WhenType`]. Defaults to `When::End`
+///
+/// ## Return:
+/// * `fv`: the value at the end of the last period
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let fv = FutureValueSchedule::from_tuple((vec![0.09, 0.11, 0.1], 0.0, -1.0, WhenType::End));
+/// println!("{:#?}'s fv is {:?}", fv, fv.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FutureValueSchedule {
+    rates: Vec<f64>,
+    pmt: f64,
+    pv: f64,
+    when: WhenType,
+}
+
+impl FutureValueSchedule {
+    /// Instantiate a `FutureValueSchedule` instance from a tuple of (`rates`, `pmt`, `pv` and `when`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, f64, f64, WhenType)) -> Self {
+        FutureValueSchedule {
+            rates: tup.0,
+            pmt: tup.1,
+            pv: tup.2,
+            when: tup.3,
+        }
+    }
+
+    /// Instantiate a `FutureValueSchedule` instance from a hash map with keys of (`rates`, `pmt`, `pv` and `when`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `FutureValueSchedule` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+
+        let rates = get_vecf64(&map, "rates").map_err(op)?;
+        let pmt = get_f64(&map, "pmt").map_err(op)?;
+        let pv = get_f64(&map, "pv").map_err(op)?;
+        let when = get_when(&map, "when").map_err(op)?;
+        Ok(FutureValueSchedule {
+            rates,
+            pmt,
+            pv,
+            when,
+        })
+    }
+
+    fn fv(&self) -> Result<f64> {
+        /*
+        Roll the value forward one period at a time
+        value = value*(1+rate) + pmt*(1+rate)**offset
+        and fv is the negative of the value at the end of the last period
+        */
+        let value = self.rates.iter().fold(self.pv, |value, &rate| {
+            value * (1.0 + rate) + self.pmt * self.when.growth(rate)
+        });
+
+        Ok(-value)
+    }
+
+    /// Get the future value from an instance of `FutureValueSchedule`
+    pub fn get(&self) -> Result<f64> {
+        self.fv()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_fvschedule_from_tuple() {
+        // FVSCHEDULE(1, {0.09, 0.11, 0.1})
+        // 1.33089
+        let fv = FutureValueSchedule::from_tuple((vec![0.09, 0.11, 0.1], 0.0, -1.0, WhenType::End));
+        let res = fv.get().unwrap();
+        let tgt = 1.33089;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_fvschedule_from_map() {
+        let mut map = ParaMap::new();
+        map.insert("rates".into(), ParaType::VecF64(vec![0.09, 0.11, 0.1]));
+        map.insert("pmt".into(), ParaType::F64(0.0));
+        map.insert("pv".into(), ParaType::F64(-1.0));
+        map.insert("when".into(), ParaType::When(WhenType::End));
+        let fv = FutureValueSchedule::from_map(map).unwrap();
+        let res = fv.get().unwrap();
+        let tgt = 1.33089;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_fvschedule_constant_rate() {
+        // a flat schedule agrees with `FutureValue`
+        for when in [WhenType::End, WhenType::Begin] {
+            let fv =
+                FutureValueSchedule::from_tuple((vec![0.075; 20], -2000.0, -500.0, when.clone()));
+            let res = fv.get().unwrap();
+            let tgt = FutureValue::from_tuple((0.075, 20, -2000.0, -500.0, when))
+                .get()
+                .unwrap();
+            assert!(
+                float_close(res, tgt, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+    }
+
+    #[test]
+    fn test_fvschedule_empty() {
+        let fv = FutureValueSchedule::from_tuple((vec![], -2000.0, -500.0, WhenType::End));
+        let res = fv.get().unwrap();
+        let tgt = 500.0;
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_fvschedule_err() {
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.09));
+        map.insert("pmt".into(), ParaType::F64(0.0));
+        map.insert("pv".into(), ParaType::F64(-1.0));
+        map.insert("when".into(), ParaType::When(WhenType::End));
+        let fv = FutureValueSchedule::from_map(map);
+        assert!(fv.is_err());
+    }
+}
@@ -0,0 +1,191 @@
+#[cfg(feature = "std")]
+use crate::{get_f64, get_vecf64, get_when, Error, ParaMap};
+use crate::{Result, WhenType};
+use alloc::vec::Vec;
+/// # Compute the future value of an irregular stream of payments
+/// This is the variant of [`crate::FutureValue`] where the payment may change from period to period,
+/// e.g. a contribution raised every year, skipped months or withdrawals
+///
+/// ## Parameters
+/// * `rate` : an interest rate compounded once per period
+/// * `pmts` : payment in each period, so the number of periods is `pmts.len()`
+/// * `pv` : present value
+/// * `when` : when payments are due [`WhenType`]. Defaults to `When::End`
+///
+/// ## Return:
+/// * `fv`: the value at the end of the last period
+/// * `path`: the value at the end of each period, whose last element is `fv`
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let fv = FutureValueStream::from_tuple((0.05 / 12.0, vec![-100.0, -100.0, 0.0, -150.0], -1000.0, WhenType::End));
+/// println!("{:#?}'s fv is {:?} via {:?}", fv, fv.get(), fv.get_path());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FutureValueStream {
+    rate: f64,
+    pmts: Vec<f64>,
+    pv: f64,
+    when: WhenType,
+}
+
+impl FutureValueStream {
+    /// Instantiate a `FutureValueStream` instance from a tuple of (`rate`, `pmts`, `pv` and `when`) in said order
+    pub fn from_tuple(tup: (f64, Vec<f64>, f64, WhenType)) -> Self {
+        FutureValueStream {
+            rate: tup.0,
+            pmts: tup.1,
+            pv: tup.2,
+            when: tup.3,
+        }
+    }
+
+    /// Instantiate a `FutureValueStream` instance from a hash map with keys of (`rate`, `pmts`, `pv` and `when`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `FutureValueStream` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+
+        let rate = get_f64(&map, "rate").map_err(op)?;
+        let pmts = get_vecf64(&map, "pmts").map_err(op)?;
+        let pv = get_f64(&map, "pv").map_err(op)?;
+        let when = get_when(&map, "when").map_err(op)?;
+        Ok(FutureValueStream {
+            rate,
+            pmts,
+            pv,
+            when,
+        })
+    }
+
+    fn path(&self) -> Result<Vec<f64>> {
+        /*
+        Roll the value forward one period at a time
+        value = value*(1+rate) + pmt*(1+rate)**offset
+        and report the negative of the value, i.e. the same sign as `fv`
+        */
+        let mut value = self.pv;
+        let path = self
+            .pmts
+            .iter()
+            .map(|&pmt| {
+                value = value * (1.0 + self.rate) + pmt * self.when.growth(self.rate);
+                -value
+            })
+            .collect();
+
+        Ok(path)
+    }
+
+    /// Get the future value from an instance of `FutureValueStream`
+    pub fn get(&self) -> Result<f64> {
+        let path = self.path()?;
+        Ok(path.last().copied().unwrap_or(-self.pv))
+    }
+
+    /// Get the value at the end of each period from an instance of `FutureValueStream`
+    pub fn get_path(&self) -> Result<Vec<f64>> {
+        self.path()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_fvstream_from_tuple() {
+        let fv =
+            FutureValueStream::from_tuple((0.1, vec![-100.0, 0.0, 50.0], -1000.0, WhenType::End));
+        // (1000*1.1 + 100)*1.1*1.1 - 50
+        // 1402.0
+        let res = fv.get().unwrap();
+        let tgt = 1402.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_fvstream_from_map() {
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.1));
+        map.insert("pmts".into(), ParaType::VecF64(vec![-100.0, 0.0, 50.0]));
+        map.insert("pv".into(), ParaType::F64(-1000.0));
+        map.insert("when".into(), ParaType::When(WhenType::Begin));
+        let fv = FutureValueStream::from_map(map).unwrap();
+        // ((1000 + 100)*1.1*1.1 - 50)*1.1
+        // 1409.1
+        let res = fv.get().unwrap();
+        let tgt = 1409.1;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_fvstream_path() {
+        let fv =
+            FutureValueStream::from_tuple((0.1, vec![-100.0, 0.0, 50.0], -1000.0, WhenType::End));
+        let res = fv.get_path().unwrap();
+        let tgt = [1200.0, 1320.0, 1402.0];
+        assert_eq!(res.len(), tgt.len());
+        for (r, t) in res.iter().zip(tgt.iter()) {
+            assert!(
+                float_close(*r, *t, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+    }
+
+    #[test]
+    fn test_fvstream_level_pmt() {
+        // a level stream agrees with `FutureValue`
+        for when in [WhenType::End, WhenType::Begin] {
+            let fv = FutureValueStream::from_tuple((0.075, vec![-2000.0; 20], 0.0, when.clone()));
+            let res = fv.get().unwrap();
+            let tgt = FutureValue::from_tuple((0.075, 20, -2000.0, 0.0, when))
+                .get()
+                .unwrap();
+            assert!(
+                float_close(res, tgt, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+    }
+
+    #[test]
+    fn test_fvstream_empty() {
+        let fv = FutureValueStream::from_tuple((0.075, vec![], -100.0, WhenType::End));
+        assert_eq!(fv.get().unwrap(), 100.0);
+        assert!(fv.get_path().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fvstream_err() {
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.1));
+        map.insert("pmt".into(), ParaType::F64(-100.0));
+        map.insert("pv".into(), ParaType::F64(-1000.0));
+        map.insert("when".into(), ParaType::When(WhenType::End));
+        let fv = FutureValueStream::from_map(map);
+        assert!(fv.is_err());
+    }
+}
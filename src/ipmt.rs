@@ -1,6 +1,10 @@
-use crate::{get_f64, get_u32, get_when, Error, FutureValue, ParaMap, Payment, Result, WhenType};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_u32, get_when, Error, ParaMap};
+use crate::{FutureValue, Payment, Result, WhenType};
 /// # Compute the interest portion of a payment
-
+///
 /// ## Parameters
 /// * `rate` : an interest rate compounded once per period
 /// * `per` : the payment period to calculate the interest amount
@@ -18,8 +22,7 @@ use crate::{get_f64, get_u32, get_when, Error, FutureValue, ParaMap, Payment, Re
 /// let ipmt = InterestPayment::from_tuple((0.1 / 12.0, 1, 24, 2000.0, 0.0, WhenType::End));
 /// println!("{:#?}'s ipmt is {:?}", ipmt, ipmt.get());
 /// ```
-
-#[derive(Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct InterestPayment {
     rate: f64,
     per: u32,
@@ -44,6 +47,7 @@ impl InterestPayment {
 
     /// Instantiate a `InterestPayment` instance from a hash map with keys of (`rate`, `per`, `nper`, `pv` and `when`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
@@ -52,12 +56,12 @@ impl InterestPayment {
             ))
         };
 
-        let rate = get_f64(&map, "rate").map_err(|err| op(err))?;
-        let per = get_u32(&map, "per").map_err(|err| op(err))?;
-        let nper = get_u32(&map, "nper").map_err(|err| op(err))?;
-        let pv = get_f64(&map, "pv").map_err(|err| op(err))?;
-        let fv = get_f64(&map, "fv").map_err(|err| op(err))?;
-        let when = get_when(&map, "when").map_err(|err| op(err))?;
+        let rate = get_f64(&map, "rate").map_err(&op)?;
+        let per = get_u32(&map, "per").map_err(&op)?;
+        let nper = get_u32(&map, "nper").map_err(&op)?;
+        let pv = get_f64(&map, "pv").map_err(&op)?;
+        let fv = get_f64(&map, "fv").map_err(&op)?;
+        let when = get_when(&map, "when").map_err(op)?;
         Ok(InterestPayment {
             rate,
             per,
@@ -101,6 +105,12 @@ impl InterestPayment {
                     }
                 }
                 WhenType::End => Some(rbl * self.rate),
+                // otherwise interest accrues for `1 - offset` of the 1st period, and for a whole period on the
+                // balance after the previous payment, i.e. `rbl` discounted by `offset` of a period, afterwards
+                _ if self.per == 1 => {
+                    Some(rbl * ((1.0 + self.rate).powf(1.0 - self.when.offset()) - 1.0))
+                }
+                _ => Some(rbl / self.when.growth(self.rate) * self.rate),
             }
             // if 0th or negative-th(not possible though since u32) payments are requested, return None
         } else {
@@ -119,7 +129,6 @@ impl InterestPayment {
 #[allow(unused_imports)]
 #[cfg(test)]
 mod tests {
-    use std::char::MAX;
 
     use crate::*;
 
@@ -136,6 +145,7 @@ mod tests {
         assert!(cond);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_ipmt_from_map() {
         let mut map = ParaMap::new();
@@ -265,6 +275,7 @@ mod tests {
         assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_ipmt_nan() {
         let mut map = ParaMap::new();
@@ -280,6 +291,7 @@ mod tests {
         assert!(cond);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_ipmt_err() {
         let mut map = ParaMap::new();
@@ -294,4 +306,38 @@ mod tests {
 
         assert!(cond);
     }
+
+    #[test]
+    fn test_ipmt_with_middle() {
+        // interest of the 1st payment accrues for half a period, i.e. -1000 * ((1 + 0.1 / 12) ** 0.5 - 1),
+        // and of later ones for a whole period on the balance after the previous payment
+        let tup = (0.1 / 12.0, 1, 12, 1000.0, 0.0, WhenType::Middle);
+        let res = InterestPayment::from_tuple(tup).get().unwrap().unwrap();
+        let tgt = -4.158022092804581;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        let tup = (0.1 / 12.0, 6, 12, 1000.0, 0.0, WhenType::Middle);
+        let res = InterestPayment::from_tuple(tup).get().unwrap().unwrap();
+        let tgt = -4.94112016905235;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        // an offset of nearly 1 is the begining of periods
+        let (begin, one) = (WhenType::Begin, WhenType::from_offset(1.0 - 1e-12).unwrap());
+        for per in [1, 2, 12] {
+            let res = InterestPayment::from_tuple((0.01, per, 12, 1000.0, 0.0, one.clone()));
+            let tgt = InterestPayment::from_tuple((0.01, per, 12, 1000.0, 0.0, begin.clone()));
+            let (res, tgt) = (res.get().unwrap().unwrap(), tgt.get().unwrap().unwrap());
+            assert!(float_close(res, tgt, RTOL, ATOL), "{} v.s. {}", res, tgt);
+        }
+    }
 }
@@ -1,13 +1,16 @@
-use crate::{float_close, get_vecf64, Error, ParaMap, Result, ATOL, RTOL};
+use crate::{float_close, Result, Summation, ATOL, RTOL};
+#[cfg(feature = "std")]
+use crate::{get_slicef64, Error, ParaMap};
+use alloc::{borrow::Cow, vec::Vec};
 /// # Compute the Internal Rate of Return (IRR)
 /// This is the "average" periodically compounded rate of return that gives a net present value of 0.0
-
+///
 /// ## Parameters
 /// `values` : array_like, shape(N,)
 /// * input cash flows per time period
 /// * by convention, net "deposits" are negative and net "withdrawals" are positive
 /// * e.g., the first element of `values`, which represents the initial investment, is typically negative
-
+///
 /// ## Return
 /// * `irr`: internal rate of return for periodic input `values`
 ///
@@ -17,71 +20,101 @@ use crate::{float_close, get_vecf64, Error, ParaMap, Result, ATOL, RTOL};
 /// let values: Vec<f64> = vec![-150000.0, 15000.0, 25000.0, 35000.0, 45000.0, 60000.0];
 /// let irr = InternalRateReturn::from_vec(values);
 /// println!("{:#?}'s irr is {:?}", irr, irr.get());
+///
+/// // borrow the cash flows instead of moving them in
+/// let values = [-150000.0, 15000.0, 25000.0, 35000.0, 45000.0, 60000.0];
+/// let irr = InternalRateReturn::from_slice(&values);
+/// println!("{:#?}'s irr is {:?}", irr, irr.get());
 /// ```
 /// ## Caveat
 /// * I use Newton-Raphson method to find first `irr` that makes the `npv` of given cash flows 0
 /// * I am still trying to find/craft packge to find roots of polynomial in similar way as `numpy_financial`
 /// * Appreciate any feedbacks
-#[derive(Debug)]
-pub struct InternalRateReturn {
-    values: Vec<f64>,
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InternalRateReturn<'a> {
+    values: Cow<'a, [f64]>,
+    summation: Summation,
 }
 
-impl InternalRateReturn {
+impl<'a> InternalRateReturn<'a> {
     /// Instantiate an `InternalRateReturn` instance from a vector of `f64`
     pub fn from_vec(values: Vec<f64>) -> Self {
         // vec must at lease be of 2 elements
         // - to raise error in future not delegat to `irr`
-        InternalRateReturn { values }
+        InternalRateReturn {
+            values: Cow::Owned(values),
+            summation: Summation::default(),
+        }
+    }
+
+    /// Instantiate an `InternalRateReturn` instance borrowing a slice of `f64`
+    pub fn from_slice(values: &'a [f64]) -> Self {
+        InternalRateReturn {
+            values: Cow::Borrowed(values),
+            summation: Summation::default(),
+        }
+    }
+
+    /// Instantiate an `InternalRateReturn` instance collecting any iterator of `f64`
+    pub fn from_cash_flows<I: IntoIterator<Item = f64>>(values: I) -> Self {
+        Self::from_vec(values.into_iter().collect())
     }
 
     /// Instantiate a `InterestPayment` instance from a hash map with keys of (`values`)
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
+        InternalRateReturn::from_map_ref(&map).map(InternalRateReturn::into_owned)
+    }
+
+    /// Instantiate an `InternalRateReturn` instance borrowing `values` from a hash map, see [`InternalRateReturn::from_map`]
+    #[cfg(feature = "std")]
+    pub fn from_map_ref(map: &'a ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
                 "Failed construct an instance of `InternalRateReturn` from: `{:?}` <- {}",
                 map, err
             ))
         };
-        let values = get_vecf64(&map, "values").map_err(|err| op(err))?;
-        Ok(InternalRateReturn { values })
+        let values = get_slicef64(map, "values").map_err(op)?;
+        Ok(InternalRateReturn::from_slice(values))
     }
 
-    fn fx(v: &Vec<f64>, x: f64) -> Result<f64> {
-        let fx: f64 = v
-            .iter()
-            .rev()
-            .enumerate()
-            .map(|(p, c)| c * x.powf(p as f64))
-            .sum();
-        Ok(fx)
+    /// Detach an instance from the cash flows it borrows, copying them if needed
+    pub fn into_owned(self) -> InternalRateReturn<'static> {
+        InternalRateReturn::from_vec(self.values.into_owned()).with_summation(self.summation)
+    }
+
+    /// Add up the terms of the polynomial with `summation`, by Horner's scheme if not set
+    pub fn with_summation(self, summation: Summation) -> Self {
+        InternalRateReturn { summation, ..self }
     }
 
-    fn dx(v: &Vec<f64>, x: f64) -> Result<f64> {
-        let dx: f64 = v
+    // the polynomial `v[0] x^(n-1) + v[1] x^(n-2) + ... + v[n-1]` of `x = 1 + irr`
+    fn fx(v: &[f64], x: f64, s: Summation) -> Result<f64> {
+        Ok(s.polyval(v.iter().rev().copied(), x, 0.0))
+    }
+
+    fn dx(v: &[f64], x: f64, s: Summation) -> Result<f64> {
+        let coefs = v
             .iter()
             .rev()
             .skip(1)
             .enumerate()
-            .map(|(p, c)| {
-                let p = p as f64;
-                c * (p + 1.0) * x.powf(p)
-            })
-            .sum();
-        Ok(dx)
+            .map(|(p, c)| c * (p as f64 + 1.0));
+        Ok(s.polyval(coefs, x, 0.0))
     }
 
     // find 1st root
-    fn find_root(v: &Vec<f64>) -> Result<Option<f64>> {
+    fn find_root(v: &[f64], s: Summation) -> Result<Option<f64>> {
         // to re-implement
         let mut x = -0.9;
         let mut iter = 0;
         while iter < 100 {
             // f
-            let f = Self::fx(v, x)?;
+            let f = Self::fx(v, x, s)?;
             // d
-            let d = Self::dx(v, x)?;
+            let d = Self::dx(v, x, s)?;
             // if d is 0, update x and continue
             if float_close(d, 0.0, RTOL, ATOL) {
                 x += 1.0;
@@ -106,16 +139,16 @@ impl InternalRateReturn {
     }
 
     // fina all possible roots- not used
-    fn _find_roots(v: &Vec<f64>) -> Result<Vec<f64>> {
+    fn _find_roots(v: &[f64], s: Summation) -> Result<Vec<f64>> {
         // to re-implement
         let mut x = -10.0;
         let mut iter = 0;
         let mut roots = Vec::<f64>::new();
         while iter < 100 {
             // f
-            let f = Self::fx(v, x)?;
+            let f = Self::fx(v, x, s)?;
             // d
-            let d = Self::dx(v, x)?;
+            let d = Self::dx(v, x, s)?;
             // d is 0, update x and continue
             if float_close(d, 0.0, RTOL, ATOL) {
                 x += 1.0;
@@ -178,8 +211,8 @@ impl InternalRateReturn {
         // Some(select_one(eirr))
 
         // For now use find_root, i.e. return one root or none
-        let irr = Self::find_root(&self.values)?.unwrap() - 1.0;
-        Ok(Some(irr))
+        let irr = Self::find_root(&self.values, self.summation)?.map(|root| root - 1.0);
+        Ok(irr)
     }
 
     /// Get the `irr` from an instance of `InternalRateReturn`
@@ -189,7 +222,7 @@ impl InternalRateReturn {
 }
 
 #[allow(unused_imports)]
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::*;
 
@@ -197,23 +230,53 @@ mod tests {
     fn test_irr_fx() {
         let c: Vec<f64> = vec![1.0, 2.0, 3.0];
         let x = 2.0;
-        let res = InternalRateReturn::fx(&c, x).unwrap();
+        let res = InternalRateReturn::fx(&c, x, Summation::Horner).unwrap();
         // 1*x^2 + 2*x^1 + 3*x^0 ->
         // 1*2^2 + 2*2^1 + 3*2^0 -> 11
         let tgt = 11.0;
         assert_eq!(res, tgt, "{} v.s. {}", res, tgt);
+        let res = InternalRateReturn::fx(&c, x, Summation::Compensated).unwrap();
+        assert_eq!(res, tgt, "{} v.s. {}", res, tgt);
     }
 
     #[test]
     fn test_irr_dx() {
         let c: Vec<f64> = vec![1.0, 2.0, 3.0];
         let x = 2.0;
-        let res = InternalRateReturn::dx(&c, x).unwrap();
+        let res = InternalRateReturn::dx(&c, x, Summation::Horner).unwrap();
         // 1*x^2 + 2*x^1 + 3*x^0 ->
         // 1*2*x^1 + 2*1*x^0 + 0 ->
         // 1*2*2^1 + 2*1*2^0 + 0 ->
         let tgt = 6.0;
         assert_eq!(res, tgt, "{} v.s. {}", res, tgt);
+        let res = InternalRateReturn::dx(&c, x, Summation::Compensated).unwrap();
+        assert_eq!(res, tgt, "{} v.s. {}", res, tgt);
+    }
+
+    #[test]
+    fn test_irr_long_series() {
+        // 10,000 periods, where terms of up to 1.7e4 cancel out to less than 1
+        // >>> from decimal import Decimal as D, getcontext
+        // >>> getcontext().prec = 80
+        // >>> values = [-6321.0] + [1.0] * 9999
+        // >>> x = D(1.0001)
+        // >>> float(sum(D(c) * x ** (9999 - p) for p, c in enumerate(values)))
+        // -0.9410411050961882
+        // >>> float(sum(D(c) * (9999 - p) * x ** (9998 - p) for p, c in enumerate(values[:-1])))
+        // -71816820.4704822
+        let mut c = vec![1.0; 10_000];
+        c[0] = -6321.0;
+        let (x, s) = (1.0001, Summation::Compensated);
+
+        let res = InternalRateReturn::fx(&c, x, s).unwrap();
+        let tgt = -0.9410411050961882;
+        assert!(float_close(res, tgt, 1e-15, 0.0), "{} v.s. {}", res, tgt);
+        let res = InternalRateReturn::fx(&c, x, Summation::Horner).unwrap();
+        assert!(float_close(res, tgt, 1e-8, 0.0), "{} v.s. {}", res, tgt);
+
+        let res = InternalRateReturn::dx(&c, x, s).unwrap();
+        let tgt = -71816820.4704822;
+        assert!(float_close(res, tgt, 1e-15, 0.0), "{} v.s. {}", res, tgt);
     }
 
     #[test]
@@ -224,8 +287,9 @@ mod tests {
         // - 0.25* x^2 + 1=0 -> x =2 and -2
         let c: Vec<f64> = vec![-0.25, 0.0, 1.0];
 
-        let root = InternalRateReturn::find_root(&c).unwrap().unwrap();
-        let tgt = InternalRateReturn::fx(&c, root).unwrap();
+        let s = Summation::Horner;
+        let root = InternalRateReturn::find_root(&c, s).unwrap().unwrap();
+        let tgt = InternalRateReturn::fx(&c, root, s).unwrap();
         let res = 0.0;
         assert!(
             float_close(res, tgt, RTOL, ATOL),
@@ -271,6 +335,29 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_irr_borrowed() {
+        let values: Vec<f64> = vec![-150000.0, 15000.0, 25000.0, 35000.0, 45000.0, 60000.0];
+        let tgt = InternalRateReturn::from_vec(values.clone()).get().unwrap();
+
+        assert_eq!(InternalRateReturn::from_slice(&values).get().unwrap(), tgt);
+        let irr = InternalRateReturn::from_cash_flows(values.iter().copied());
+        assert_eq!(irr.get().unwrap(), tgt);
+
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(values));
+        let irr = InternalRateReturn::from_map_ref(&map).unwrap();
+        assert_eq!(irr.get().unwrap(), tgt);
+    }
+
+    #[test]
+    fn test_irr_no_root() {
+        // mixed signs but 100 - 100x + 100x^2 has no real root, so the iteration does not converge
+        let values: Vec<f64> = vec![100.0, -100.0, 100.0];
+        let res = InternalRateReturn::from_vec(values).get().unwrap();
+        assert_eq!(res, None);
+    }
+
     #[test]
     fn test_irr_err() {
         let values: Vec<f64> = vec![-150000.0, 15000.0, 25000.0, 35000.0, 45000.0, 60000.0];
@@ -11,12 +11,45 @@
 //! * pv - present value
 //! * rate - rate of interest per period
 //! * irr - internal rate of return
-//! * npv - net present value of a cash flow series
+//! * npv - net present value of a cash flow series, also mid-period or at fractional times
+//! * summation - Horner's scheme or compensated summation for npv and irr of long cash flows
 //! * mirr - modified internal rate of return
+//! * arm - amortization schedule of an adjustable-rate loan
+//! * fvschedule, pvschedule, npvschedule - fv, pv and npv with a rate for each period
+//! * fvstream - future value of an irregular stream of payments
+//! * payback - simple and discounted payback period of a cash flow series
+//! * budget - profitability index, benefit-cost ratio, equivalent annual annuity and cost
+//! * compare - crossover rates, incremental irr and npv profile of two projects
+//! * table - npv profile and two-way sensitivity tables with csv export
+//! * simulation - seeded Monte Carlo simulation of npv and irr
+//! * scenario - what-if scenarios and tornado chart data over `ParaMap` inputs
+//! * registry - evaluate any function by name from a `ParaMap`
+//! * calculator - `Calculator` trait implemented by every calculator, with batch evaluation in parallel behind the `rayon` feature
+//! * excel - spreadsheet-compatible functions with Excel names, argument order and errors
+//! * formula - evaluate spreadsheet formulas such as `=PMT(0.05/12, 360, -250000)`
+//! * python - Python bindings as a drop-in for `numpy_financial`, behind the `python` feature
+//! * wasm - WebAssembly bindings for JavaScript, behind the `wasm` feature
+//! * capi - C ABI with a generated header, behind the `capi` feature
+//! * array - fv, pv, pmt and alike broadcast over `ndarray` views, and irr, npv and mirr along an axis, behind the `ndarray` feature
+//! * columnar - pmt, ipmt, ppmt and balance columns of Arrow loan tapes, behind the `arrow` feature
+//! * csv - read cash flows and loan tapes from CSV and write results and schedules, behind the `csv` feature
 
 //! ## To Be Added
 //! * amortization
 
+//! ## Features
+//! * `std` - enabled by default, everything including `ParaMap` and the functions built on it
+//! * `alloc` - without `std`, the calculators of cash flows and schedules, which need `Vec`
+//! * `libm` - required without `std`, for `powf`, `ln` and alike
+//! * `python`, `wasm`, `capi` - bindings for Python, JavaScript and C
+//! * `rayon` - evaluate `Calculator::batch` and `Calculator::batch_params` in parallel
+//! * `ndarray` - the `array` module of functions over `ndarray` views
+//! * `arrow` - the `columnar` module of functions over Arrow arrays and record batches
+//! * `csv` - the `csv` module of readers and writers of cash flows, loan tapes and schedules
+//!
+//! Without any of `std` and `alloc` the crate is `no_std` and provides `fv`, `pv`, `pmt`, `nper`, `ipmt`, `ppmt`
+//! and `rate` through `from_tuple`
+
 //! ## Tests
 //! * All test cases are tested against `numpy_financial`'s result with some exceptions
 //! * `numpy_financial` has some its own issues
@@ -36,29 +69,124 @@
 //! ## Contribution
 //! * Use the crate and feedback
 //! * Submit pull request or issues though the GitHub repository
+// Reference values in the tests are kept as printed by numpy_financial.
+#![cfg_attr(test, allow(clippy::excessive_precision))]
+#![cfg_attr(not(feature = "std"), no_std)]
+// the test harness links `std`, whose float methods take precedence over those of `math::Float`
+#![cfg_attr(all(test, not(feature = "std")), allow(unused_imports))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("`rfinancial` requires either the `std` or the `libm` feature");
 
+#[cfg(feature = "alloc")]
+mod arm;
+#[cfg(feature = "ndarray")]
+pub mod array;
+#[cfg(feature = "alloc")]
+mod budget;
+#[cfg(feature = "std")]
+mod calculator;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+#[cfg(feature = "alloc")]
+mod compare;
+#[cfg(feature = "csv")]
+pub mod csv;
 mod error;
+#[cfg(feature = "alloc")]
+pub mod excel;
+#[cfg(feature = "std")]
+pub mod formula;
 mod fv;
+#[cfg(feature = "alloc")]
+mod fvschedule;
+#[cfg(feature = "alloc")]
+mod fvstream;
 mod ipmt;
+#[cfg(feature = "alloc")]
 mod irr;
+#[cfg(not(feature = "std"))]
+mod math;
+#[cfg(feature = "alloc")]
 mod mirr;
 mod nper;
+#[cfg(feature = "alloc")]
 mod npv;
+#[cfg(feature = "alloc")]
+mod npvschedule;
+#[cfg(feature = "alloc")]
+mod payback;
 mod pmt;
 mod ppmt;
 mod pv;
+#[cfg(feature = "alloc")]
+mod pvschedule;
+#[cfg(feature = "python")]
+mod python;
 mod rate;
+#[cfg(feature = "std")]
+mod registry;
+#[cfg(feature = "std")]
+mod scenario;
+#[cfg(feature = "alloc")]
+mod simulation;
+#[cfg(feature = "alloc")]
+mod summation;
+#[cfg(feature = "alloc")]
+mod table;
 mod util;
+#[cfg(feature = "wasm")]
+mod wasm;
 
+#[cfg(feature = "alloc")]
+pub use crate::arm::{AdjustableRateLoan, AmortizationRow};
+#[cfg(feature = "alloc")]
+pub use crate::budget::{
+    BenefitCostRatio, EquivalentAnnualAnnuity, EquivalentAnnualCost, ProfitabilityIndex,
+};
+#[cfg(feature = "std")]
+pub use crate::calculator::Calculator;
+#[cfg(feature = "alloc")]
+pub use crate::compare::ProjectComparison;
 pub use crate::error::*;
 pub use crate::fv::*;
+#[cfg(feature = "alloc")]
+pub use crate::fvschedule::FutureValueSchedule;
+#[cfg(feature = "alloc")]
+pub use crate::fvstream::FutureValueStream;
 pub use crate::ipmt::InterestPayment;
+#[cfg(feature = "alloc")]
 pub use crate::irr::InternalRateReturn;
+#[cfg(feature = "alloc")]
 pub use crate::mirr::ModifiedIRR;
 pub use crate::nper::NumberPeriod;
+#[cfg(feature = "alloc")]
 pub use crate::npv::NetPresentValue;
+#[cfg(feature = "alloc")]
+pub use crate::npvschedule::NetPresentValueSchedule;
+#[cfg(feature = "alloc")]
+pub use crate::payback::{DiscountedPaybackPeriod, PaybackPeriod};
 pub use crate::pmt::Payment;
 pub use crate::ppmt::PrincipalPayment;
 pub use crate::pv::PresentValue;
+#[cfg(feature = "alloc")]
+pub use crate::pvschedule::PresentValueSchedule;
 pub use crate::rate::Rate;
+#[cfg(feature = "std")]
+pub use crate::registry::{evaluate, parameters, CalcResult, ParaKind, ParaSpec, FUNCTIONS};
+#[cfg(feature = "std")]
+pub use crate::scenario::{Override, Scenario, ScenarioAnalysis, ScenarioRow, TornadoBar};
+#[cfg(feature = "alloc")]
+pub use crate::simulation::{Distribution, MonteCarlo, SimulationResult, Summary};
+#[cfg(feature = "alloc")]
+pub use crate::summation::Summation;
+#[cfg(feature = "std")]
+pub use crate::table::SensitivityTable;
+#[cfg(feature = "alloc")]
+pub use crate::table::{NpvProfile, Table};
 pub use crate::util::*;
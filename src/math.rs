@@ -0,0 +1,89 @@
+//! Float methods of `std` for `no_std` builds, computed by `libm`
+// not every method is used without `alloc`
+#[allow(dead_code)]
+pub(crate) trait Float {
+    fn abs(self) -> f64;
+    fn ceil(self) -> f64;
+    fn cos(self) -> f64;
+    fn exp(self) -> f64;
+    fn floor(self) -> f64;
+    fn fract(self) -> f64;
+    fn ln(self) -> f64;
+    fn mul_add(self, a: f64, b: f64) -> f64;
+    fn powf(self, n: f64) -> f64;
+    fn powi(self, n: i32) -> f64;
+    fn sqrt(self) -> f64;
+}
+
+impl Float for f64 {
+    fn abs(self) -> f64 {
+        libm::fabs(self)
+    }
+
+    fn ceil(self) -> f64 {
+        libm::ceil(self)
+    }
+
+    fn cos(self) -> f64 {
+        libm::cos(self)
+    }
+
+    fn exp(self) -> f64 {
+        libm::exp(self)
+    }
+
+    fn floor(self) -> f64 {
+        libm::floor(self)
+    }
+
+    fn fract(self) -> f64 {
+        self - libm::trunc(self)
+    }
+
+    fn ln(self) -> f64 {
+        libm::log(self)
+    }
+
+    fn mul_add(self, a: f64, b: f64) -> f64 {
+        libm::fma(self, a, b)
+    }
+
+    fn powf(self, n: f64) -> f64 {
+        libm::pow(self, n)
+    }
+
+    fn powi(self, n: i32) -> f64 {
+        libm::pow(self, n as f64)
+    }
+
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+}
+
+// Called as `Float::method(x)`, since the float methods of `std` linked by the test harness take precedence
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{float_close, ATOL, RTOL};
+
+    #[test]
+    fn test_math_libm() {
+        let cases = [
+            (Float::powf(1.1, 2.5), 1.2690587062858836),
+            (Float::powi(1.05, -12), 0.5568374181775593),
+            (Float::exp(0.5), 1.6487212707001282),
+            (Float::ln(2.5), 0.9162907318741551),
+            (Float::cos(1.0), 0.5403023058681398),
+            (Float::sqrt(6.25), 2.5),
+            (Float::mul_add(2.0, 3.0, 1.0), 7.0),
+            (Float::abs(-2.5), 2.5),
+            (Float::ceil(-2.5), -2.0),
+            (Float::floor(-2.5), -3.0),
+            (Float::fract(-2.5), -0.5),
+        ];
+        for (res, tgt) in cases {
+            assert!(float_close(res, tgt, RTOL, ATOL), "{} v.s. {}", res, tgt);
+        }
+    }
+}
@@ -1,7 +1,12 @@
-use crate::{get_f64, get_vecf64, Error, ParaMap, Result};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_slicef64, Error, ParaMap};
+use crate::{Result, Summation};
+use alloc::{borrow::Cow, vec::Vec};
 
 /// # Compute the Modified Internal Rate of Return (MIRR)
-
+///
 /// MIRR is a financial metric that takes into account both the cost of the investment and the return on reinvested cash flows.
 /// It is useful for evaluating the profitability of an investment with multiple cash inflows and outflows.
 ///
@@ -9,38 +14,66 @@ use crate::{get_f64, get_vecf64, Error, ParaMap, Result};
 /// * `values` : array_like. It must contain at least one positive and one negative value
 /// * `finance_rate` : interest rate paid on the cash flows
 /// * `reinvest_rate` : interest rate received on the cash flows upon reinvestment
-
+///
 /// ## Return:
 /// * `mirr`: the modified internal rate of return
-
+///
 /// ## Example
 /// ```rust
 /// use rfinancial::*;
 /// let tup = (vec![100.0, 200.0, -50.0, 300.00, -200.0], 0.05, 0.06);
 /// let mirr = ModifiedIRR::from_tuple(tup);
 /// println!("\n{:#?}'s mirr is {:#?}", mirr, mirr.get());
+///
+/// // borrow the cash flows instead of moving them in
+/// let values = [100.0, 200.0, -50.0, 300.00, -200.0];
+/// let mirr = ModifiedIRR::from_slice((&values, 0.05, 0.06));
+/// println!("\n{:#?}'s mirr is {:#?}", mirr, mirr.get());
 /// ```
-
-#[derive(Debug)]
-pub struct ModifiedIRR {
-    values: Vec<f64>,
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModifiedIRR<'a> {
+    values: Cow<'a, [f64]>,
     finance_rate: f64,
     reinvest_rate: f64,
+    summation: Summation,
 }
 
-impl ModifiedIRR {
+impl<'a> ModifiedIRR<'a> {
     /// Instantiate an instance of `ModifiedIRR` from a tuple of `(Vec<f64>, f64, f64>)` in said order
     pub fn from_tuple(tup: (Vec<f64>, f64, f64)) -> Self {
         ModifiedIRR {
-            values: tup.0,
+            values: Cow::Owned(tup.0),
+            finance_rate: tup.1,
+            reinvest_rate: tup.2,
+            summation: Summation::default(),
+        }
+    }
+
+    /// Instantiate an instance of `ModifiedIRR` borrowing `values`, from a tuple of `(&[f64], f64, f64>)` in said order
+    pub fn from_slice(tup: (&'a [f64], f64, f64)) -> Self {
+        ModifiedIRR {
+            values: Cow::Borrowed(tup.0),
             finance_rate: tup.1,
             reinvest_rate: tup.2,
+            summation: Summation::default(),
         }
     }
 
+    /// Instantiate an instance of `ModifiedIRR` collecting `values`, from a tuple of `(I, f64, f64>)` in said order
+    pub fn from_cash_flows<I: IntoIterator<Item = f64>>(tup: (I, f64, f64)) -> Self {
+        Self::from_tuple((tup.0.into_iter().collect(), tup.1, tup.2))
+    }
+
     /// Instantiate a `ModifiedIRR` instance from a hash map with keys of (`values`, `finance_rate`, `reinvest_rate`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
+        ModifiedIRR::from_map_ref(&map).map(ModifiedIRR::into_owned)
+    }
+
+    /// Instantiate a `ModifiedIRR` instance borrowing `values` from a hash map, see [`ModifiedIRR::from_map`]
+    #[cfg(feature = "std")]
+    pub fn from_map_ref(map: &'a ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
                 "Failed construct an instance of `ModifiedIRR` from: `{:?}` <- {}",
@@ -48,44 +81,60 @@ impl ModifiedIRR {
             ))
         };
 
-        let values = get_vecf64(&map, "values").map_err(|err| op(err))?;
-        let finance_rate = get_f64(&map, "finance_rate").map_err(|err| op(err))?;
-        let reinvest_rate = get_f64(&map, "reinvest_rate").map_err(|err| op(err))?;
-        Ok(ModifiedIRR {
+        let values = get_slicef64(map, "values").map_err(&op)?;
+        let finance_rate = get_f64(map, "finance_rate").map_err(&op)?;
+        let reinvest_rate = get_f64(map, "reinvest_rate").map_err(op)?;
+        Ok(ModifiedIRR::from_slice((
             values,
             finance_rate,
             reinvest_rate,
-        })
+        )))
+    }
+
+    /// Detach an instance from the cash flows it borrows, copying them if needed
+    pub fn into_owned(self) -> ModifiedIRR<'static> {
+        ModifiedIRR {
+            values: Cow::Owned(self.values.into_owned()),
+            finance_rate: self.finance_rate,
+            reinvest_rate: self.reinvest_rate,
+            summation: self.summation,
+        }
+    }
+
+    /// Add up the discounted cash flows with `summation`, by Horner's scheme if not set
+    pub fn with_summation(self, summation: Summation) -> Self {
+        ModifiedIRR { summation, ..self }
     }
 
     fn mirr(&self) -> Result<Option<f64>> {
         let any_negative = self.values.iter().any(|&v| v <= 0.0);
         let any_positive = self.values.iter().any(|&v| v > 0.0);
         if !(any_negative & any_positive) {
+            #[cfg(feature = "std")]
             println!("No real solution exists for MIRR since  all cashflows are of the same sign.");
             Ok(None)
         } else {
             // v * neg
-            let neg_pmts: Vec<f64> = self
+            let neg_pmts = self
                 .values
                 .iter()
-                .map(|&rf| if rf < 0.0 { rf } else { 0.0 })
-                .collect();
+                .map(|&rf| if rf < 0.0 { rf } else { 0.0 });
 
             // v * pos
-            let pos_pmts: Vec<f64> = self
+            let pos_pmts = self
                 .values
                 .iter()
-                .map(|&rf| if rf > 0.0 { rf } else { 0.0 })
-                .collect();
+                .map(|&rf| if rf > 0.0 { rf } else { 0.0 });
 
             // numer = np.abs(npv(rr, v * pos))
-            let numer = crate::NetPresentValue::from_tuple((pos_pmts, self.reinvest_rate))
+            let numer = crate::NetPresentValue::from_cash_flows((pos_pmts, self.reinvest_rate))
+                .with_summation(self.summation)
                 .get()?
                 .abs();
 
             // denom = np.abs(npv(fr, v * neg))
-            let denom = crate::NetPresentValue::from_tuple((neg_pmts, self.finance_rate))
+            let denom = crate::NetPresentValue::from_cash_flows((neg_pmts, self.finance_rate))
+                .with_summation(self.summation)
                 .get()?
                 .abs();
 
@@ -103,7 +152,7 @@ impl ModifiedIRR {
 }
 
 #[allow(unused_imports)]
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use std::collections::btree_map::Values;
 
@@ -162,6 +211,26 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_mirr_borrowed() {
+        let values = vec![100.0, 200.0, -50.0, 300.00, -200.0];
+        let tgt = ModifiedIRR::from_tuple((values.clone(), 0.05, 0.06))
+            .get()
+            .unwrap();
+
+        let res = ModifiedIRR::from_slice((&values, 0.05, 0.06))
+            .get()
+            .unwrap();
+        assert_eq!(res, tgt);
+
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(values));
+        map.insert("finance_rate".to_string(), ParaType::F64(0.05));
+        map.insert("reinvest_rate".to_string(), ParaType::F64(0.06));
+        let res = ModifiedIRR::from_map_ref(&map).unwrap().get().unwrap();
+        assert_eq!(res, tgt);
+    }
+
     #[test]
     fn test_mirr_no_solution() {
         let mirr = ModifiedIRR::from_tuple((
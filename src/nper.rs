@@ -1,6 +1,10 @@
-use crate::{get_f64, get_when, Error, ParaMap, Result, WhenType};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_when, Error, ParaMap};
+use crate::{Result, WhenType};
 /// # Compute the number of periodic payments
-
+///
 /// ## Parameters
 /// * `rate` : an interest rate compounded once per period
 /// * `pmt` : payment in each period
@@ -18,7 +22,7 @@ use crate::{get_f64, get_when, Error, ParaMap, Result, WhenType};
 /// println!("{:#?}'s nper is {:?}", nper, nper.get());
 /// ```
 ///
-#[derive(Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct NumberPeriod {
     rate: f64,
     pmt: f64,
@@ -41,6 +45,7 @@ impl NumberPeriod {
 
     /// Instantiate a `NumberPeriod ` instance from a hash map with keys of (`rate`, `pmt`, `pv`, `fv` and `when`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
@@ -48,11 +53,11 @@ impl NumberPeriod {
                 map, err
             ))
         };
-        let rate = get_f64(&map, "rate").map_err(|err| op(err))?;
-        let pmt = get_f64(&map, "pmt").map_err(|err| op(err))?;
-        let pv = get_f64(&map, "pv").map_err(|err| op(err))?;
-        let fv = get_f64(&map, "fv").map_err(|err| op(err))?;
-        let when = get_when(&map, "when").map_err(|err| op(err))?;
+        let rate = get_f64(&map, "rate").map_err(&op)?;
+        let pmt = get_f64(&map, "pmt").map_err(&op)?;
+        let pv = get_f64(&map, "pv").map_err(&op)?;
+        let fv = get_f64(&map, "fv").map_err(&op)?;
+        let when = get_when(&map, "when").map_err(op)?;
         Ok(NumberPeriod {
             rate,
             pmt,
@@ -65,7 +70,7 @@ impl NumberPeriod {
     fn nper(&self) -> Result<Option<f64>> {
         /*
         Solve below equation if rate is not 0
-        fv + pv*(1+rate)**nper + pmt*(1+rate*when)/rate*((1+rate)**nper-1) = 0
+        fv + pv*(1+rate)**nper + pmt*(1+rate)**offset/rate*((1+rate)**nper-1) = 0, with `offset` of `when`
         but if rate is 0 then
         fv + pv + pmt*nper = 0
         */
@@ -84,8 +89,7 @@ impl NumberPeriod {
         // We know that rate_ != 0, we don't need to check for division by 0
         // z = pmt_ * (1.0 + rate_ * when_) / rate_
         // return log((-fv_ + z) / (pv_ + z)) / log(1.0 + rate_)
-        let when_f64 = self.when.clone() as u8 as f64;
-        let z = self.pmt * (1.0 + self.rate * when_f64) / self.rate;
+        let z = self.pmt * self.when.growth(self.rate) / self.rate;
         Ok(Some(
             ((-self.fv + z) / (self.pv + z)).ln() / (1.0 + self.rate).ln(),
         ))
@@ -118,6 +122,7 @@ mod tests {
         // [50, 21.544944],  # Computed using Google Sheet's NPER
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_nper_from_map() {
         let mut map = ParaMap::new();
@@ -174,6 +179,7 @@ mod tests {
         assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_nper_err() {
         let mut map = ParaMap::new();
@@ -189,6 +195,7 @@ mod tests {
         assert!(cond);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     #[ignore = "need to figure out how to produce nan"]
     fn test_nper_nan() {
@@ -205,4 +212,17 @@ mod tests {
         let cond = nper.get().unwrap().unwrap().is_nan();
         assert!(cond);
     }
+
+    #[test]
+    fn test_nper_with_middle() {
+        let tup = (0.075, -2000.0, 0.0, 89798.49848593227, WhenType::Middle);
+        let res = NumberPeriod::from_tuple(tup).get().unwrap().unwrap();
+        let tgt = 20.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
 }
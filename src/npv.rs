@@ -1,10 +1,19 @@
-use crate::{get_f64, get_vecf64, Error, ParaMap, Result};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_slicef64, get_when, ParaMap};
+use crate::{summation::discount, Error, Result, Summation, WhenType};
+use alloc::{borrow::Cow, vec::Vec};
 
 /// # Compute the net present value of a cash flow, given an interest rate
-
+///
 /// ## Parameters
 /// * `rate` : an interest rate compounded once per period
 /// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period
+/// * `when` : when the payments after the first are due in their periods [`WhenType`], i.e. `values[t]` at `t - offset`.
+///   Defaults to `When::End`, set with `with_when`, e.g. `WhenType::Middle` for the mid-period convention
+/// * `times` : the time of each payment in periods, e.g. `[0.0, 0.25, 1.25]` with a stub period, instead of
+///   `0, 1, 2...` and `when`. Set with `with_times`
 ///
 /// ## Return:
 /// * `ipmt`: the net present value
@@ -15,47 +24,158 @@ use crate::{get_f64, get_vecf64, Error, ParaMap, Result};
 /// let tup = (vec![-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0], 0.05);
 /// let npv = NetPresentValue::from_tuple(tup);
 /// println!("{:#?}'s npv is {:?}", npv, npv.get());
+///
+/// // borrow the cash flows instead of moving them in
+/// let values = [-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0];
+/// let npv = NetPresentValue::from_slice((&values, 0.05));
+/// println!("{:#?}'s npv is {:?}", npv, npv.get());
+///
+/// // payments in the middle of the periods, and after a stub period of a quarter
+/// let npv = NetPresentValue::from_slice((&values, 0.05)).with_when(WhenType::Middle);
+/// println!("{:#?}'s npv is {:?}", npv, npv.get());
+/// let times = [0.0, 0.25, 1.25, 2.25, 3.25, 4.25];
+/// let npv = NetPresentValue::from_slice((&values, 0.05)).with_times(&times[..]);
+/// println!("{:#?}'s npv is {:?}", npv, npv.get());
 /// ```
-
-#[derive(Debug)]
-pub struct NetPresentValue {
-    values: Vec<f64>,
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetPresentValue<'a> {
+    values: Cow<'a, [f64]>,
     rate: f64,
+    summation: Summation,
+    when: WhenType,
+    times: Option<Cow<'a, [f64]>>,
 }
 
-impl NetPresentValue {
-    /// Instantiate a `ModifiedIRR` instance from a vec of (`values`, `rate`) in said order
+impl<'a> NetPresentValue<'a> {
+    /// Instantiate a `NetPresentValue` instance from a tuple of (`values`, `rate`) in said order
     pub fn from_tuple(tup: (Vec<f64>, f64)) -> Self {
         NetPresentValue {
-            values: tup.0,
+            values: Cow::Owned(tup.0),
             rate: tup.1,
+            summation: Summation::default(),
+            when: WhenType::default(),
+            times: None,
         }
     }
 
-    /// Instantiate a `NetPresentValue ` instance from a hash map with keys of (`values`, `rate`) in said order
+    /// Instantiate a `NetPresentValue` instance borrowing `values`, from a tuple of (`values`, `rate`) in said order
+    pub fn from_slice(tup: (&'a [f64], f64)) -> Self {
+        NetPresentValue {
+            values: Cow::Borrowed(tup.0),
+            rate: tup.1,
+            summation: Summation::default(),
+            when: WhenType::default(),
+            times: None,
+        }
+    }
+
+    /// Instantiate a `NetPresentValue` instance collecting `values`, from a tuple of (`values`, `rate`) in said order
+    pub fn from_cash_flows<I: IntoIterator<Item = f64>>(tup: (I, f64)) -> Self {
+        Self::from_tuple((tup.0.into_iter().collect(), tup.1))
+    }
+
+    /// Instantiate a `NetPresentValue ` instance from a hash map with keys of (`values`, `rate`) in said order, and
+    /// optionally `when` and `times`
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
+        NetPresentValue::from_map_ref(&map).map(NetPresentValue::into_owned)
+    }
+
+    /// Instantiate a `NetPresentValue` instance borrowing `values` from a hash map, see [`NetPresentValue::from_map`]
+    #[cfg(feature = "std")]
+    pub fn from_map_ref(map: &'a ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
                 "Failed construct an instance of `NetPresentValue` from: `{:?}` <- {}",
                 map, err
             ))
         };
-        let values = get_vecf64(&map, "values").map_err(|err| op(err))?;
-        let rate = get_f64(&map, "rate").map_err(|err| op(err))?;
-        Ok(NetPresentValue { values, rate })
+        let values = get_slicef64(map, "values").map_err(&op)?;
+        let rate = get_f64(map, "rate").map_err(&op)?;
+        let mut npv = NetPresentValue::from_slice((values, rate));
+        if map.contains_key("when") {
+            npv = npv.with_when(get_when(map, "when").map_err(&op)?);
+        }
+        if map.contains_key("times") {
+            npv = npv.with_times(get_slicef64(map, "times").map_err(op)?);
+        }
+        Ok(npv)
+    }
+
+    /// Detach an instance from the cash flows it borrows, copying them if needed
+    pub fn into_owned(self) -> NetPresentValue<'static> {
+        NetPresentValue {
+            values: Cow::Owned(self.values.into_owned()),
+            rate: self.rate,
+            summation: self.summation,
+            when: self.when,
+            times: self.times.map(|times| Cow::Owned(times.into_owned())),
+        }
+    }
+
+    /// Add up the discounted cash flows with `summation`, by Horner's scheme if not set
+    pub fn with_summation(self, summation: Summation) -> Self {
+        NetPresentValue { summation, ..self }
+    }
+
+    /// Pay the cash flows after the first `when` in their periods, at the end if not set
+    pub fn with_when(self, when: WhenType) -> Self {
+        NetPresentValue { when, ..self }
+    }
+
+    /// Pay each cash flow at its own time in periods, which may be fractional, rather than at `0, 1, 2...`
+    pub fn with_times<T: Into<Cow<'a, [f64]>>>(self, times: T) -> Self {
+        NetPresentValue {
+            times: Some(times.into()),
+            ..self
+        }
     }
 
     fn npv(&self) -> Result<f64> {
-        let npv: f64 = self
-            .values
-            .iter()
-            .enumerate()
-            .map(|(p, &c)| {
-                let p = p as f64;
-                c * (1.0 + self.rate).powf(-p)
-            })
-            .sum();
+        if let Some(times) = &self.times {
+            if times.len() != self.values.len() {
+                return Err(Error::ParaError("times: one for each of the values".into()));
+            }
+            let base = 1.0 + self.rate;
+            let terms = self
+                .values
+                .iter()
+                .zip(times.iter())
+                .map(|(&v, &t)| v / base.powf(t));
+            return Ok(self.summation.sum(terms));
+        }
+
+        // Horner's scheme is `0 * inf` at `rate = -1`, so sum the terms by `powf` for their limit of `±inf`
+        if 1.0 + self.rate == 0.0 {
+            let offset = self.when.offset();
+            let npv = self
+                .values
+                .iter()
+                .enumerate()
+                .map(|(p, &v)| {
+                    let t = if p == 0 { 0.0 } else { p as f64 - offset };
+                    v * 0.0_f64.powf(-t)
+                })
+                .sum();
+            return Ok(npv);
+        }
+
+        // a polynomial in the discount factor, evaluated without `powf`
+        let (d, d_err) = discount(self.rate);
+        let Some((&first, rest)) = self.values.split_first() else {
+            return Ok(0.0);
+        };
+        let npv = match self.when {
+            WhenType::End => self
+                .summation
+                .polyval(self.values.iter().copied(), d, d_err),
+            // the later cash flows are brought forward by `offset` of a period
+            _ => {
+                let later = self.summation.polyval(rest.iter().copied(), d, d_err) * d;
+                first + self.when.growth(self.rate) * later
+            }
+        };
 
         Ok(npv)
     }
@@ -66,7 +186,7 @@ impl NetPresentValue {
 }
 
 #[allow(unused_imports)]
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::*;
 
@@ -107,6 +227,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_npv_long_series() {
+        // 10,000 periods of 1.0 against 6321.0 up front, which almost cancel out
+        // >>> from decimal import Decimal as D, getcontext
+        // >>> getcontext().prec = 80
+        // >>> values = [-6321.0] + [1.0] * 9999
+        // >>> d = 1 / (1 + D(0.0001))
+        // >>> float(sum(D(c) * d ** t for t, c in enumerate(values)))
+        // -0.34624160561434925
+        // while npf.npv(0.0001, values) is -0.3462416053153143
+        let mut values = vec![1.0; 10_000];
+        values[0] = -6321.0;
+        let tgt = -0.34624160561434925;
+        let npv = NetPresentValue::from_slice((&values, 0.0001));
+        let res = npv
+            .clone()
+            .with_summation(Summation::Compensated)
+            .get()
+            .unwrap();
+        assert!(
+            float_close(res, tgt, 1e-15, 0.0),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+        let res = npv.get().unwrap();
+        assert!(
+            float_close(res, tgt, 1e-8, 0.0),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        // 10,000 periods of 1e8 + 1 and -1e8 + 1 in turn, where the 1s are lost in rounding
+        // >>> values = [(1e8 if t % 2 == 0 else -1e8) + 1.0 for t in range(10_000)]
+        // >>> d = 1 / (1 + D(0.05))
+        // >>> float(sum(D(c) * d ** t for t, c in enumerate(values)))
+        // 51219533.19512195
+        let values: Vec<f64> = (0..10_000)
+            .map(|t| if t % 2 == 0 { 1e8 } else { -1e8 } + 1.0)
+            .collect();
+        let tgt = 51219533.19512195;
+        let npv = NetPresentValue::from_slice((&values, 0.05));
+        let res = npv.with_summation(Summation::Compensated).get().unwrap();
+        assert_eq!(res, tgt);
+    }
+
+    #[test]
+    fn test_npv_borrowed() {
+        let values = vec![-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0];
+        let tgt = NetPresentValue::from_tuple((values.clone(), 0.05))
+            .get()
+            .unwrap();
+
+        let res = NetPresentValue::from_slice((&values, 0.05)).get().unwrap();
+        assert_eq!(res, tgt);
+
+        let res = NetPresentValue::from_cash_flows((values.iter().copied(), 0.05))
+            .get()
+            .unwrap();
+        assert_eq!(res, tgt);
+
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(values));
+        map.insert("rate".to_string(), ParaType::F64(0.05));
+        let npv = NetPresentValue::from_map_ref(&map).unwrap();
+        assert_eq!(npv.get().unwrap(), tgt);
+        assert_eq!(npv.into_owned(), NetPresentValue::from_map(map).unwrap());
+    }
+
     #[test]
     fn test_npv_zero_rate() {
         // npf.npv(0.05, [-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0])
@@ -123,6 +313,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_npv_minus_one_rate() {
+        // npf.npv(-1, [-100, 50, 60]) is inf, with a warning of division by zero
+        let npv = NetPresentValue::from_tuple((vec![-100.0, 50.0, 60.0], -1.0));
+        assert_eq!(npv.get().unwrap(), f64::INFINITY);
+        let npv = NetPresentValue::from_tuple((vec![100.0, -50.0], -1.0));
+        assert_eq!(npv.get().unwrap(), f64::NEG_INFINITY);
+        let npv = NetPresentValue::from_tuple((vec![-100.0, 50.0, 60.0], -1.0))
+            .with_summation(Summation::Compensated)
+            .with_when(WhenType::Middle);
+        assert_eq!(npv.get().unwrap(), f64::INFINITY);
+        // only the first value is not discounted
+        let npv = NetPresentValue::from_tuple((vec![-100.0], -1.0));
+        assert_eq!(npv.get().unwrap(), -100.0);
+    }
+
     #[test]
     fn test_npv_err() {
         let values = vec![-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0];
@@ -135,4 +341,102 @@ mod tests {
         let cond = npv.is_err();
         assert!(cond);
     }
+
+    #[test]
+    fn test_npv_with_when() {
+        let values = [-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0];
+        // the cash flows after the first in the middle of the periods
+        // >>> v[0] + sum(x / 1.05**(i - 0.5) for i, x in enumerate(v) if i)
+        // 496.35590174659956
+        let npv = NetPresentValue::from_slice((&values, 0.05)).with_when(WhenType::Middle);
+        let res = npv.get().unwrap();
+        let tgt = 496.35590174659956;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+        let res = npv.with_summation(Summation::Compensated).get().unwrap();
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        // >>> v[0] + sum(x / 1.05**(i - 0.25) for i, x in enumerate(v) if i)
+        // 308.48656585660865
+        let npv = NetPresentValue::from_slice((&values, 0.05))
+            .with_when(WhenType::from_offset(0.25).unwrap());
+        let res = npv.get().unwrap();
+        let tgt = 308.48656585660865;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(values.to_vec()));
+        map.insert("rate".to_string(), ParaType::F64(0.05));
+        map.insert("when".to_string(), ParaType::Str("middle".to_string()));
+        let res = NetPresentValue::from_map(map).unwrap().get().unwrap();
+        let tgt = 496.35590174659956;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_npv_with_times() {
+        // a stub period of a quarter before whole periods
+        // >>> sum(x / 1.05**t for x, t in zip(v, [0, 0.25, 1.25, 2.25, 3.25, 4.25]))
+        // 686.5308141686628
+        let values = [-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0];
+        let times = vec![0.0, 0.25, 1.25, 2.25, 3.25, 4.25];
+        let npv = NetPresentValue::from_slice((&values, 0.05)).with_times(times.clone());
+        let res = npv.get().unwrap();
+        let tgt = 686.5308141686628;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        // whole times are the cash flows of `numpy_financial`
+        let whole = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        let res = NetPresentValue::from_slice((&values, 0.05))
+            .with_times(&whole[..])
+            .get()
+            .unwrap();
+        let tgt = 122.89485495093959;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(values.to_vec()));
+        map.insert("rate".to_string(), ParaType::F64(0.05));
+        map.insert("times".to_string(), ParaType::VecF64(times));
+        let res = NetPresentValue::from_map(map).unwrap().get().unwrap();
+        let tgt = 686.5308141686628;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        let npv = NetPresentValue::from_slice((&values, 0.05)).with_times(&whole[1..]);
+        assert!(npv.get().is_err());
+    }
 }
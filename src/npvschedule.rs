@@ -0,0 +1,139 @@
+#[cfg(feature = "std")]
+use crate::{get_vecf64, ParaMap};
+use crate::{Error, Result};
+use alloc::{format, vec::Vec};
+
+/// # Compute the net present value of a cash flow, given a rate for each period
+/// This is the variant of [`crate::NetPresentValue`] where each period has its own discount rate
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period
+/// * `rates` : the same length as `values`; `rates[t]` is the rate from `t` to `t+1`, so `values[t]` is discounted
+///   by `(1+rates[0])*...*(1+rates[t-1])` and the last rate is not used
+///
+/// ## Return:
+/// * `npv`: the net present value
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let tup = (vec![-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0], vec![0.05, 0.05, 0.06, 0.06, 0.07, 0.07]);
+/// let npv = NetPresentValueSchedule::from_tuple(tup);
+/// println!("{:#?}'s npv is {:?}", npv, npv.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetPresentValueSchedule {
+    values: Vec<f64>,
+    rates: Vec<f64>,
+}
+
+impl NetPresentValueSchedule {
+    /// Instantiate a `NetPresentValueSchedule` instance from a tuple of (`values`, `rates`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, Vec<f64>)) -> Self {
+        NetPresentValueSchedule {
+            values: tup.0,
+            rates: tup.1,
+        }
+    }
+
+    /// Instantiate a `NetPresentValueSchedule` instance from a hash map with keys of (`values`, `rates`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `NetPresentValueSchedule` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        let rates = get_vecf64(&map, "rates").map_err(op)?;
+        Ok(NetPresentValueSchedule { values, rates })
+    }
+
+    fn npv(&self) -> Result<f64> {
+        if self.values.len() != self.rates.len() {
+            return Err(Error::ParaError(format!(
+                "rates: expected {} elements as values, got {}",
+                self.values.len(),
+                self.rates.len()
+            )));
+        }
+
+        let mut discount = 1.0;
+        let mut npv = 0.0;
+        for (c, r) in self.values.iter().zip(self.rates.iter()) {
+            npv += c * discount;
+            discount /= 1.0 + r;
+        }
+
+        Ok(npv)
+    }
+
+    /// Get the net present value from an instance of `NetPresentValueSchedule`
+    pub fn get(&self) -> Result<f64> {
+        self.npv()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_npvschedule_from_tuple() {
+        let tup = (
+            vec![-1000.0, 300.0, 400.0, 500.0],
+            vec![0.05, 0.1, 0.15, 0.2],
+        );
+        let npv = NetPresentValueSchedule::from_tuple(tup);
+        let res = npv.get().unwrap();
+        let tgt = -1000.0 + 300.0 / 1.05 + 400.0 / 1.05 / 1.1 + 500.0 / 1.05 / 1.1 / 1.15;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_npvschedule_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0]),
+        );
+        map.insert("rates".to_string(), ParaType::VecF64(vec![0.05; 6]));
+
+        // a flat schedule agrees with `NetPresentValue`
+        // npf.npv(0.05, [-15000.0, 1500.0, 2500.0, 3500.0, 4500.0, 6000.0])
+        // 122.89485495093959
+        let npv = NetPresentValueSchedule::from_map(map).unwrap();
+        let res = npv.get().unwrap();
+        let tgt = 122.89485495093959;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_npvschedule_length_mismatch() {
+        let tup = (vec![-1000.0, 300.0, 400.0, 500.0], vec![0.05, 0.1]);
+        let npv = NetPresentValueSchedule::from_tuple(tup);
+        assert!(npv.get().is_err());
+    }
+
+    #[test]
+    fn test_npvschedule_err() {
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(vec![-1000.0, 300.0]));
+        map.insert("rate".to_string(), ParaType::F64(0.05));
+        let npv = NetPresentValueSchedule::from_map(map);
+        assert!(npv.is_err());
+    }
+}
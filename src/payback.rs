@@ -0,0 +1,272 @@
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::Result;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_vecf64, Error, ParaMap};
+use alloc::vec::Vec;
+
+/// # Compute the payback period of a cash flow
+/// This is the time it takes for the cumulative cash flow to turn non-negative after an outflow, interpolated within
+/// the period where it crosses zero
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period
+///
+/// ## Return:
+/// * `payback`: the payback period or `None` if the cash flow never pays back
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let payback = PaybackPeriod::from_vec(vec![-1000.0, 300.0, 400.0, 500.0]);
+/// println!("{:#?}'s payback is {:?}", payback, payback.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PaybackPeriod {
+    values: Vec<f64>,
+}
+
+/// # Compute the discounted payback period of a cash flow, given an interest rate
+/// This is the time it takes for the cumulative discounted cash flow to turn non-negative after an outflow,
+/// interpolated within the period where it crosses zero
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period
+/// * `rate` : an interest rate compounded once per period
+///
+/// ## Return:
+/// * `payback`: the discounted payback period or `None` if the cash flow never pays back
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let payback = DiscountedPaybackPeriod::from_tuple((vec![-1000.0, 300.0, 400.0, 500.0], 0.1));
+/// println!("{:#?}'s payback is {:?}", payback, payback.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiscountedPaybackPeriod {
+    values: Vec<f64>,
+    rate: f64,
+}
+
+/// First time the cumulative sum of `values` turns non-negative after an outflow, with linear interpolation within
+/// the period, or `0` if it is never negative
+fn payback(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let mut cumulative = 0.0;
+    // leading zeros or inflows do not pay back an outflow yet to come
+    let mut outflow = false;
+    let mut empty = true;
+    for (t, c) in values.enumerate() {
+        empty = false;
+        let previous = cumulative;
+        cumulative += c;
+        if cumulative < 0.0 {
+            outflow = true;
+        } else if outflow {
+            return Some((t - 1) as f64 + -previous / c);
+        }
+    }
+    if outflow || empty {
+        None
+    } else {
+        Some(0.0)
+    }
+}
+
+impl PaybackPeriod {
+    /// Instantiate a `PaybackPeriod` instance from a vector of `f64`
+    pub fn from_vec(values: Vec<f64>) -> Self {
+        PaybackPeriod { values }
+    }
+
+    /// Instantiate a `PaybackPeriod` instance from a hash map with keys of (`values`)
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `PaybackPeriod` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        Ok(PaybackPeriod { values })
+    }
+
+    /// Get the payback period from an instance of `PaybackPeriod`
+    pub fn get(&self) -> Result<Option<f64>> {
+        Ok(payback(self.values.iter().copied()))
+    }
+}
+
+impl DiscountedPaybackPeriod {
+    /// Instantiate a `DiscountedPaybackPeriod` instance from a tuple of (`values`, `rate`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, f64)) -> Self {
+        DiscountedPaybackPeriod {
+            values: tup.0,
+            rate: tup.1,
+        }
+    }
+
+    /// Instantiate a `DiscountedPaybackPeriod` instance from a hash map with keys of (`values`, `rate`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `DiscountedPaybackPeriod` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        let rate = get_f64(&map, "rate").map_err(op)?;
+        Ok(DiscountedPaybackPeriod { values, rate })
+    }
+
+    /// Get the discounted payback period from an instance of `DiscountedPaybackPeriod`
+    pub fn get(&self) -> Result<Option<f64>> {
+        let discounted = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(p, &c)| c * (1.0 + self.rate).powf(-(p as f64)));
+        Ok(payback(discounted))
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_payback_from_vec() {
+        // -1000 -> -700 -> -300 -> 200, i.e. 2 + 300/500
+        let payback = PaybackPeriod::from_vec(vec![-1000.0, 300.0, 400.0, 500.0]);
+        let res = payback.get().unwrap().unwrap();
+        let tgt = 2.6;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_payback_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 500.0, 500.0, 500.0]),
+        );
+        let payback = PaybackPeriod::from_map(map).unwrap();
+        let res = payback.get().unwrap().unwrap();
+        let tgt = 2.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_payback_never() {
+        let payback = PaybackPeriod::from_vec(vec![-1000.0, 300.0, 400.0]);
+        let res = payback.get().unwrap();
+        let tgt = None;
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_payback_no_investment() {
+        let payback = PaybackPeriod::from_vec(vec![100.0, 300.0]);
+        let res = payback.get().unwrap();
+        let tgt = Some(0.0);
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_payback_leading_zero() {
+        // 0 -> -100 -> -40 -> 20, i.e. 2 + 40/60
+        let values = vec![0.0, -100.0, 60.0, 60.0];
+        let res = PaybackPeriod::from_vec(values.clone())
+            .get()
+            .unwrap()
+            .unwrap();
+        let tgt = 2.0 + 40.0 / 60.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        let res = DiscountedPaybackPeriod::from_tuple((values, 0.0))
+            .get()
+            .unwrap()
+            .unwrap();
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+        assert_eq!(PaybackPeriod::from_vec(vec![]).get().unwrap(), None);
+    }
+
+    #[test]
+    fn test_discounted_payback_from_tuple() {
+        // pays back in simple terms but not once discounted
+        // -1000 + 300/1.1 + 400/1.1^2 + 500/1.1^3 = -21.037
+        let payback =
+            DiscountedPaybackPeriod::from_tuple((vec![-1000.0, 300.0, 400.0, 500.0], 0.1));
+        let res = payback.get().unwrap();
+        let tgt = None;
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_discounted_payback_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 600.0, 600.0, 600.0]),
+        );
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        let payback = DiscountedPaybackPeriod::from_map(map).unwrap();
+        let res = payback.get().unwrap().unwrap();
+        // -1000 + 600/1.1 = -454.545, 600/1.1^2 = 495.868, i.e. 1 + 454.545/495.868
+        let tgt = 1.0 + (1000.0 - 600.0 / 1.1) / (600.0 / 1.1 / 1.1);
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_discounted_payback_zero_rate() {
+        // with zero rate it is the same as the simple payback
+        let values = vec![-1000.0, 300.0, 400.0, 500.0];
+        let res = DiscountedPaybackPeriod::from_tuple((values.clone(), 0.0))
+            .get()
+            .unwrap();
+        let tgt = PaybackPeriod::from_vec(values).get().unwrap();
+        assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
+    }
+
+    #[test]
+    fn test_payback_err() {
+        let mut map = ParaMap::new();
+        map.insert("Values".to_string(), ParaType::VecF64(vec![-1000.0, 300.0]));
+        map.insert("rate".to_string(), ParaType::F64(0.1));
+        assert!(PaybackPeriod::from_map(map).is_err());
+
+        let mut map = ParaMap::new();
+        map.insert("values".to_string(), ParaType::VecF64(vec![-1000.0, 300.0]));
+        assert!(DiscountedPaybackPeriod::from_map(map).is_err());
+    }
+}
@@ -1,6 +1,10 @@
-use crate::{get_f64, get_u32, get_when, Error, ParaMap, Result, WhenType};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_u32, get_when, Error, ParaMap};
+use crate::{Result, WhenType};
 /// # Compute the payment against loan principal plus interest
-
+///
 /// ## Parameters
 /// * `rate` : an interest rate compounded once per period
 /// * `nper` : number of periodic payments
@@ -17,7 +21,7 @@ use crate::{get_f64, get_u32, get_when, Error, ParaMap, Result, WhenType};
 /// let pmt = Payment::from_tuple((0.08 / 12.0, 60, 15000.0, 0.0, WhenType::End));
 /// println!("{:#?}'s pmt is {:?}", pmt, pmt.get());
 /// ```
-#[derive(Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Payment {
     rate: f64,
     nper: u32,
@@ -40,6 +44,7 @@ impl Payment {
 
     /// Instantiate a `Payment` instance from a hash map with keys of (`rate`, `nper`, `pv`, `fv`, and `when`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
@@ -48,11 +53,11 @@ impl Payment {
             ))
         };
 
-        let rate = get_f64(&map, "rate").map_err(|err| op(err))?;
-        let nper = get_u32(&map, "nper").map_err(|err| op(err))?;
-        let pv = get_f64(&map, "pv").map_err(|err| op(err))?;
-        let fv = get_f64(&map, "fv").map_err(|err| op(err))?;
-        let when = get_when(&map, "when").map_err(|err| op(err))?;
+        let rate = get_f64(&map, "rate").map_err(&op)?;
+        let nper = get_u32(&map, "nper").map_err(&op)?;
+        let pv = get_f64(&map, "pv").map_err(&op)?;
+        let fv = get_f64(&map, "fv").map_err(&op)?;
+        let when = get_when(&map, "when").map_err(op)?;
         Ok(Payment {
             rate,
             nper,
@@ -65,15 +70,14 @@ impl Payment {
     fn pmt(&self) -> Result<f64> {
         /*
         Solve below equation if rate is not 0
-        fv + pv*(1+rate)**nper + pmt*(1+rate*when)/rate*((1+rate)**nper-1) = 0
+        fv + pv*(1+rate)**nper + pmt*(1+rate)**offset/rate*((1+rate)**nper-1) = 0, with `offset` of `when`
         but if rate is 0 then
         fv + pv + pmt*nper = 0
         */
         if self.rate != 0.0 {
             let tmp = (1.0 + self.rate).powf(self.nper as f64);
             let pv_future = self.pv * tmp;
-            let when_f64 = self.when.clone() as u8 as f64;
-            let fact = (1.0 + self.rate * when_f64) / self.rate * (tmp - 1.0);
+            let fact = self.when.growth(self.rate) / self.rate * (tmp - 1.0);
             Ok(-(self.fv + pv_future) / fact)
         } else {
             Ok(-(self.pv + self.fv) / self.nper as f64)
@@ -105,6 +109,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_pmt_from_map() {
         let mut map = ParaMap::new();
@@ -180,6 +185,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_pmt_err() {
         let mut map = ParaMap::new();
@@ -193,4 +199,18 @@ mod tests {
         let cond = pmt.is_err();
         assert!(cond)
     }
+
+    #[test]
+    fn test_pmt_with_middle() {
+        // the payment into a future value of payments in the middle of the periods
+        let pmt = Payment::from_tuple((0.075, 20, 0.0, 89798.49848593227, WhenType::Middle));
+        let res = pmt.get().unwrap();
+        let tgt = -2000.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
 }
@@ -1,8 +1,8 @@
-use crate::{
-    get_f64, get_u32, get_when, Error, InterestPayment, ParaMap, Payment, Result, WhenType,
-};
+#[cfg(feature = "std")]
+use crate::{get_f64, get_u32, get_when, Error, ParaMap};
+use crate::{InterestPayment, Payment, Result, WhenType};
 /// # Compute the payment against loan principal
-
+///
 /// ## Parameters
 /// * `rate` : an interest rate compounded once per period
 /// * `per` : the payment period to calculate the interest amount
@@ -20,8 +20,7 @@ use crate::{
 /// let ppmt = PrincipalPayment::from_tuple((0.1 / 12.0, 1, 24, 2000.0, 0.0, WhenType::End));
 /// println!("{:#?}'s ppmt is {:?}", ppmt, ppmt.get());
 /// ```
-
-#[derive(Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct PrincipalPayment {
     rate: f64,
     per: u32,
@@ -46,6 +45,7 @@ impl PrincipalPayment {
 
     /// Instantiate a `PrincipalPayment` instance from a hash map with keys of (`rate`, `per`, `nper`,`pv`, `fv`, and `when`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
@@ -54,12 +54,12 @@ impl PrincipalPayment {
             ))
         };
 
-        let rate = get_f64(&map, "rate").map_err(|err| op(err))?;
-        let per = get_u32(&map, "per").map_err(|err| op(err))?;
-        let nper = get_u32(&map, "nper").map_err(|err| op(err))?;
-        let pv = get_f64(&map, "pv").map_err(|err| op(err))?;
-        let fv = get_f64(&map, "fv").map_err(|err| op(err))?;
-        let when = get_when(&map, "when").map_err(|err| op(err))?;
+        let rate = get_f64(&map, "rate").map_err(&op)?;
+        let per = get_u32(&map, "per").map_err(&op)?;
+        let nper = get_u32(&map, "nper").map_err(&op)?;
+        let pv = get_f64(&map, "pv").map_err(&op)?;
+        let fv = get_f64(&map, "fv").map_err(&op)?;
+        let when = get_when(&map, "when").map_err(op)?;
         Ok(PrincipalPayment {
             rate,
             per,
@@ -91,10 +91,7 @@ impl PrincipalPayment {
         ))
         .get()?;
 
-        let ppmt = match ipmt {
-            Some(value) => Some(total_pmt - value),
-            None => None,
-        };
+        let ppmt = ipmt.map(|value| total_pmt - value);
 
         Ok(ppmt)
     }
@@ -125,6 +122,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_ppmt_from_map() {
         let mut map = ParaMap::new();
@@ -227,6 +225,7 @@ mod tests {
         assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_ppmt_err() {
         let mut map = ParaMap::new();
@@ -240,4 +239,22 @@ mod tests {
         let cond = ppmt.is_err();
         assert!(cond);
     }
+
+    #[test]
+    fn test_ppmt_with_middle() {
+        // the principal portions of payments due in the middle of periods repay the loan
+        let res: f64 = (1..=12)
+            .map(|per| {
+                let tup = (0.1 / 12.0, per, 12, 1000.0, 0.0, WhenType::Middle);
+                PrincipalPayment::from_tuple(tup).get().unwrap().unwrap()
+            })
+            .sum();
+        let tgt = -1000.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
 }
@@ -1,6 +1,10 @@
-use crate::{get_f64, get_u32, get_when, util::WhenType, Error, ParaMap, Result};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_u32, get_when, Error, ParaMap};
+use crate::{util::WhenType, Result};
 /// # Compute the present value
-
+///
 /// ## Parameters
 /// * `rate` : an interest rate compounded once per period
 /// * `nper` : number of compounding periods
@@ -17,7 +21,7 @@ use crate::{get_f64, get_u32, get_when, util::WhenType, Error, ParaMap, Result};
 /// let pv = PresentValue::from_tuple((0.075, 20, -2000.0, 0.0, WhenType::End));
 /// println!("{:#?}'s pv is {:?}", pv, pv.get());
 /// ```
-#[derive(Debug)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct PresentValue {
     rate: f64,
     nper: u32,
@@ -40,6 +44,7 @@ impl PresentValue {
 
     /// Instantiate a `PresentValue` instance from a hash map with keys of (`rate`, `nper`,`pmt`, `fv`, and `when`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
@@ -48,11 +53,11 @@ impl PresentValue {
             ))
         };
 
-        let rate = get_f64(&map, "rate").map_err(|err| op(err))?;
-        let nper = get_u32(&map, "nper").map_err(|err| op(err))?;
-        let pmt = get_f64(&map, "pmt").map_err(|err| op(err))?;
-        let fv = get_f64(&map, "fv").map_err(|err| op(err))?;
-        let when = get_when(&map, "when").map_err(|err| op(err))?;
+        let rate = get_f64(&map, "rate").map_err(&op)?;
+        let nper = get_u32(&map, "nper").map_err(&op)?;
+        let pmt = get_f64(&map, "pmt").map_err(&op)?;
+        let fv = get_f64(&map, "fv").map_err(&op)?;
+        let when = get_when(&map, "when").map_err(op)?;
         Ok(PresentValue {
             rate,
             nper,
@@ -65,14 +70,13 @@ impl PresentValue {
     fn fv(&self) -> Result<f64> {
         /*
         Solve below equation if rate is not 0
-        fv + pv*(1+rate)**nper + pmt*(1+rate*when)/rate*((1+rate)**nper-1) = 0
+        fv + pv*(1+rate)**nper + pmt*(1+rate)**offset/rate*((1+rate)**nper-1) = 0, with `offset` of `when`
         but if rate is 0 then
         fv + pv + pmt*nper = 0
         */
         if self.rate != 0.0 {
             let temp = (1.0 + self.rate).powf(self.nper as f64);
-            let when_f64 = self.when.clone() as u8 as f64;
-            let fact = (1.0 + self.rate * when_f64) * (temp - 1.0) / self.rate;
+            let fact = self.when.growth(self.rate) * (temp - 1.0) / self.rate;
             Ok(-(self.fv + self.pmt * fact) / temp)
         } else {
             Ok(-self.fv - self.pmt * self.nper as f64)
@@ -106,6 +110,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_pv_from_map() {
         let mut map = ParaMap::new();
@@ -209,6 +214,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_pv_err() {
         let mut map = ParaMap::new();
@@ -220,4 +226,19 @@ mod tests {
         let pv = PresentValue::from_map(map);
         assert!(pv.is_err())
     }
+
+    #[test]
+    fn test_pv_with_middle() {
+        // >>> -2000 * 1.075**0.5 * (1.075**20 - 1) / 0.075 / 1.075**20
+        // 21139.74721968893
+        let pv = PresentValue::from_tuple((0.075, 20, -2000.0, 0.0, WhenType::Middle));
+        let res = pv.get().unwrap();
+        let tgt = 21139.74721968893;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
 }
@@ -0,0 +1,170 @@
+#[cfg(feature = "std")]
+use crate::{get_f64, get_vecf64, get_when, Error, ParaMap};
+use crate::{Result, WhenType};
+use alloc::vec::Vec;
+/// # Compute the present value through a schedule of per-period rates
+/// This is the variant of [`crate::PresentValue`] where each period has its own discount rate
+///
+/// ## Parameters
+/// * `rates` : an interest rate for each compounding period, so the number of periods is `rates.len()`
+/// * `pmt` : payment in each period
+/// * `fv` : future value
+/// * `when` : when payments are due [`WhenType`]. Defaults to `When::End`
+///
+/// ## Return:
+/// * `pv`: the present value of a series of payments
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let pv = PresentValueSchedule::from_tuple((vec![0.07, 0.075, 0.08], 12000.0, 0.0, WhenType::End));
+/// println!("{:#?}'s pv is {:?}", pv, pv.get());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PresentValueSchedule {
+    rates: Vec<f64>,
+    pmt: f64,
+    fv: f64,
+    when: WhenType,
+}
+
+impl PresentValueSchedule {
+    /// Instantiate a `PresentValueSchedule` instance from a tuple of (`rates`, `pmt`, `fv` and `when`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, f64, f64, WhenType)) -> Self {
+        PresentValueSchedule {
+            rates: tup.0,
+            pmt: tup.1,
+            fv: tup.2,
+            when: tup.3,
+        }
+    }
+
+    /// Instantiate a `PresentValueSchedule` instance from a hash map with keys of (`rates`, `pmt`, `fv` and `when`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `PresentValueSchedule` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+
+        let rates = get_vecf64(&map, "rates").map_err(op)?;
+        let pmt = get_f64(&map, "pmt").map_err(op)?;
+        let fv = get_f64(&map, "fv").map_err(op)?;
+        let when = get_when(&map, "when").map_err(op)?;
+        Ok(PresentValueSchedule {
+            rates,
+            pmt,
+            fv,
+            when,
+        })
+    }
+
+    fn pv(&self) -> Result<f64> {
+        /*
+        Roll the value backward one period at a time, starting from the last one
+        value = (value + pmt*(1+rate)**offset) / (1+rate)
+        and pv is the negative of the value at present
+        */
+        let value = self.rates.iter().rev().fold(self.fv, |value, &rate| {
+            (value + self.pmt * self.when.growth(rate)) / (1.0 + rate)
+        });
+
+        Ok(-value)
+    }
+
+    /// Get the present value from an instance of `PresentValueSchedule`
+    pub fn get(&self) -> Result<f64> {
+        self.pv()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_pvschedule_from_tuple() {
+        // -(100/1.05 + 100/1.05/1.1 + 1100/1.05/1.1/1.15)
+        // -1090.2929
+        let pv =
+            PresentValueSchedule::from_tuple((vec![0.05, 0.1, 0.15], 100.0, 1000.0, WhenType::End));
+        let res = pv.get().unwrap();
+        let tgt = -(100.0 / 1.05 + 100.0 / 1.05 / 1.1 + 1100.0 / 1.05 / 1.1 / 1.15);
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_pvschedule_from_map() {
+        let mut map = ParaMap::new();
+        map.insert("rates".into(), ParaType::VecF64(vec![0.05, 0.1, 0.15]));
+        map.insert("pmt".into(), ParaType::F64(100.0));
+        map.insert("fv".into(), ParaType::F64(1000.0));
+        map.insert("when".into(), ParaType::When(WhenType::Begin));
+        let pv = PresentValueSchedule::from_map(map).unwrap();
+        let res = pv.get().unwrap();
+        let tgt = -(100.0 + 100.0 / 1.05 + 100.0 / 1.05 / 1.1 + 1000.0 / 1.05 / 1.1 / 1.15);
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_pvschedule_constant_rate() {
+        // a flat schedule agrees with `PresentValue`
+        for when in [WhenType::End, WhenType::Begin] {
+            let pv =
+                PresentValueSchedule::from_tuple((vec![0.07; 20], 12000.0, 5000.0, when.clone()));
+            let res = pv.get().unwrap();
+            let tgt = PresentValue::from_tuple((0.07, 20, 12000.0, 5000.0, when))
+                .get()
+                .unwrap();
+            assert!(
+                float_close(res, tgt, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+    }
+
+    #[test]
+    fn test_pvschedule_roundtrip() {
+        let rates = vec![0.03, 0.045, 0.02, 0.06];
+        let pv = PresentValueSchedule::from_tuple((rates.clone(), -250.0, 1000.0, WhenType::End))
+            .get()
+            .unwrap();
+        let res = FutureValueSchedule::from_tuple((rates, -250.0, pv, WhenType::End))
+            .get()
+            .unwrap();
+        let tgt = 1000.0;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_pvschedule_err() {
+        let mut map = ParaMap::new();
+        map.insert("rates".into(), ParaType::F64(0.05));
+        map.insert("pmt".into(), ParaType::F64(100.0));
+        map.insert("fv".into(), ParaType::F64(1000.0));
+        map.insert("when".into(), ParaType::When(WhenType::End));
+        let pv = PresentValueSchedule::from_map(map);
+        assert!(pv.is_err());
+    }
+}
@@ -0,0 +1,661 @@
+//! # Python bindings
+//! A drop-in for `numpy_financial` built with the `python` feature, e.g. with `maturin develop --features python`.
+//! Functions take `numpy_financial`'s signatures and broadcast scalars, lists and numpy arrays against each other.
+//! A scalar is returned when all arguments are scalars and a numpy array otherwise.
+//!
+//! Differences from `numpy_financial`:
+//! * invalid arguments raise `ValueError`, while results without a solution are `nan` unless `raise_exceptions=True`
+//!
+//! ## Example
+//! ```python
+//! import rfinancial as npf
+//! npf.pmt(0.075 / 12, 12 * 15, 200000)
+//! npf.fv([0.05, 0.06, 0.07] , 10, -100, -100)
+//! ```
+use crate::excel::growth;
+use crate::{
+    Error, InternalRateReturn, ModifiedIRR, NetPresentValue, NumberPeriod, Rate, WhenType,
+};
+use numpy::ndarray::{ArrayD, Axis, IxDyn};
+use numpy::{AllowTypeChange, IntoPyArray, PyArrayLikeDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyFloat, PyString};
+
+fn value_error(err: Error) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// A scalar, a list or a numpy array as an array of `f64`
+fn array(obj: &Bound<'_, PyAny>) -> PyResult<ArrayD<f64>> {
+    if let Ok(v) = obj.extract::<f64>() {
+        return Ok(ArrayD::from_elem(IxDyn(&[]), v));
+    }
+    let arr = obj.extract::<PyArrayLikeDyn<'_, f64, AllowTypeChange>>()?;
+    Ok(arr.as_array().to_owned())
+}
+
+/// An optional argument defaulting to `0.0`
+fn optional(obj: Option<&Bound<'_, PyAny>>) -> PyResult<ArrayD<f64>> {
+    obj.map(array)
+        .transpose()
+        .map(|arr| arr.unwrap_or_else(|| ArrayD::zeros(IxDyn(&[]))))
+}
+
+/// `when` as an array of `0.0` for end and `1.0` for begin, accepting `numpy_financial`'s strings
+fn when_array(obj: Option<&Bound<'_, PyAny>>) -> PyResult<ArrayD<f64>> {
+    let Some(obj) = obj else {
+        return Ok(ArrayD::from_elem(IxDyn(&[]), 0.0));
+    };
+    if let Ok(s) = obj.cast::<PyString>() {
+        let v = match s.to_str()? {
+            "end" | "finish" => 0.0,
+            "begin" | "start" => 1.0,
+            other => return Err(PyValueError::new_err(format!("invalid `when`: {}", other))),
+        };
+        return Ok(ArrayD::from_elem(IxDyn(&[]), v));
+    }
+    array(obj)
+}
+
+fn when(v: f64) -> PyResult<WhenType> {
+    match v {
+        0.0 => Ok(WhenType::End),
+        1.0 => Ok(WhenType::Begin),
+        _ => Err(PyValueError::new_err(format!("invalid `when`: {}", v))),
+    }
+}
+
+// `nper` and `per` may be fractional or negative as in `numpy_financial`, so the time value of money is computed in
+// closed form rather than by the calculators taking a `u32`
+
+fn future_value(rate: f64, nper: f64, pmt: f64, pv: f64, when: WhenType) -> f64 {
+    let (t, a) = growth(rate, nper, when);
+    -(pv * t + pmt * a)
+}
+
+fn present_value(rate: f64, nper: f64, pmt: f64, fv: f64, when: WhenType) -> f64 {
+    let (t, a) = growth(rate, nper, when);
+    -(fv + pmt * a) / t
+}
+
+fn payment(rate: f64, nper: f64, pv: f64, fv: f64, when: WhenType) -> f64 {
+    let (t, a) = growth(rate, nper, when);
+    -(fv + pv * t) / a
+}
+
+/// Interest of the `per`th payment, i.e. the interest on the balance after `per - 1` payments
+fn interest(rate: f64, per: f64, nper: f64, pv: f64, fv: f64, when: WhenType) -> f64 {
+    let pmt = payment(rate, nper, pv, fv, when.clone());
+    let ipmt = future_value(rate, per - 1.0, pmt, pv, when.clone()) * rate;
+    match when {
+        // payments start at the first period
+        _ if per < 1.0 => f64::NAN,
+        // no interest has accrued before the first payment at the beginning
+        WhenType::Begin if per == 1.0 => 0.0,
+        WhenType::Begin => ipmt / (1.0 + rate),
+        _ => ipmt,
+    }
+}
+
+/// A missing solution is `nan` unless exceptions are requested
+fn solution(res: Option<f64>, raise_exceptions: bool) -> PyResult<f64> {
+    match res {
+        Some(v) => Ok(v),
+        None if raise_exceptions => Err(PyValueError::new_err("no solution found")),
+        None => Ok(f64::NAN),
+    }
+}
+
+/// Shape of arrays broadcast against each other by numpy's rules
+fn broadcast_shape(shapes: &[&[usize]]) -> PyResult<Vec<usize>> {
+    let ndim = shapes.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut shape = vec![1; ndim];
+    for s in shapes {
+        for (i, &n) in s.iter().enumerate() {
+            let dim = &mut shape[ndim - s.len() + i];
+            match (*dim, n) {
+                (d, n) if d == n || n == 1 => {}
+                (1, n) => *dim = n,
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "operands could not be broadcast together with shapes {:?}",
+                        shapes
+                    )))
+                }
+            }
+        }
+    }
+    Ok(shape)
+}
+
+/// A float for a 0-d array and a numpy array otherwise
+fn output(py: Python<'_>, out: ArrayD<f64>) -> Py<PyAny> {
+    if out.ndim() == 0 {
+        PyFloat::new(py, out.into_iter().next().unwrap_or(f64::NAN))
+            .into_any()
+            .unbind()
+    } else {
+        out.into_pyarray(py).into_any().unbind()
+    }
+}
+
+/// Apply `f` elementwise over broadcast arguments
+fn broadcast<const N: usize>(
+    py: Python<'_>,
+    args: [ArrayD<f64>; N],
+    f: impl Fn([f64; N]) -> PyResult<f64>,
+) -> PyResult<Py<PyAny>> {
+    let shapes: Vec<&[usize]> = args.iter().map(|a| a.shape()).collect();
+    let shape = broadcast_shape(&shapes)?;
+    let views = args
+        .iter()
+        .map(|a| a.broadcast(IxDyn(&shape)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| PyValueError::new_err("operands could not be broadcast together"))?;
+    let mut out = ArrayD::zeros(IxDyn(&shape));
+    for (idx, o) in out.indexed_iter_mut() {
+        let vals = std::array::from_fn(|i| views[i][&idx]);
+        *o = f(vals)?;
+    }
+    Ok(output(py, out))
+}
+
+/// Apply `f` to each cash flow along the last axis of `values`
+fn along_last_axis(
+    py: Python<'_>,
+    values: &Bound<'_, PyAny>,
+    f: impl Fn(Vec<f64>) -> PyResult<f64>,
+) -> PyResult<Py<PyAny>> {
+    let values = array(values)?;
+    if values.ndim() == 0 {
+        return Err(PyValueError::new_err(
+            "`values` must be an array of cash flows",
+        ));
+    }
+    let last = Axis(values.ndim() - 1);
+    let out = values
+        .lanes(last)
+        .into_iter()
+        .map(|lane| f(lane.to_vec()))
+        .collect::<PyResult<Vec<f64>>>()?;
+    let shape = &values.shape()[..values.ndim() - 1];
+    let out = ArrayD::from_shape_vec(IxDyn(shape), out)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(output(py, out))
+}
+
+/// `fv(rate, nper, pmt, pv, when='end')`
+#[pyfunction]
+#[pyo3(signature = (rate, nper, pmt, pv, when = None))]
+fn fv(
+    py: Python<'_>,
+    rate: &Bound<'_, PyAny>,
+    nper: &Bound<'_, PyAny>,
+    pmt: &Bound<'_, PyAny>,
+    pv: &Bound<'_, PyAny>,
+    when: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let args = [
+        array(rate)?,
+        array(nper)?,
+        array(pmt)?,
+        array(pv)?,
+        when_array(when)?,
+    ];
+    broadcast(py, args, |[rate, nper, pmt, pv, w]| {
+        Ok(future_value(rate, nper, pmt, pv, self::when(w)?))
+    })
+}
+
+/// `pv(rate, nper, pmt, fv=0, when='end')`
+#[pyfunction]
+#[pyo3(signature = (rate, nper, pmt, fv = None, when = None))]
+fn pv(
+    py: Python<'_>,
+    rate: &Bound<'_, PyAny>,
+    nper: &Bound<'_, PyAny>,
+    pmt: &Bound<'_, PyAny>,
+    fv: Option<&Bound<'_, PyAny>>,
+    when: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let args = [
+        array(rate)?,
+        array(nper)?,
+        array(pmt)?,
+        optional(fv)?,
+        when_array(when)?,
+    ];
+    broadcast(py, args, |[rate, nper, pmt, fv, w]| {
+        Ok(present_value(rate, nper, pmt, fv, self::when(w)?))
+    })
+}
+
+/// `pmt(rate, nper, pv, fv=0, when='end')`
+#[pyfunction]
+#[pyo3(signature = (rate, nper, pv, fv = None, when = None))]
+fn pmt(
+    py: Python<'_>,
+    rate: &Bound<'_, PyAny>,
+    nper: &Bound<'_, PyAny>,
+    pv: &Bound<'_, PyAny>,
+    fv: Option<&Bound<'_, PyAny>>,
+    when: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let args = [
+        array(rate)?,
+        array(nper)?,
+        array(pv)?,
+        optional(fv)?,
+        when_array(when)?,
+    ];
+    broadcast(py, args, |[rate, nper, pv, fv, w]| {
+        Ok(payment(rate, nper, pv, fv, self::when(w)?))
+    })
+}
+
+/// `nper(rate, pmt, pv, fv=0, when='end')`
+#[pyfunction]
+#[pyo3(signature = (rate, pmt, pv, fv = None, when = None))]
+fn nper(
+    py: Python<'_>,
+    rate: &Bound<'_, PyAny>,
+    pmt: &Bound<'_, PyAny>,
+    pv: &Bound<'_, PyAny>,
+    fv: Option<&Bound<'_, PyAny>>,
+    when: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let args = [
+        array(rate)?,
+        array(pmt)?,
+        array(pv)?,
+        optional(fv)?,
+        when_array(when)?,
+    ];
+    broadcast(py, args, |[rate, pmt, pv, fv, w]| {
+        let tup = (rate, pmt, pv, fv, self::when(w)?);
+        let res = NumberPeriod::from_tuple(tup).get().map_err(value_error)?;
+        solution(res, false)
+    })
+}
+
+/// `ipmt(rate, per, nper, pv, fv=0, when='end')`
+#[pyfunction]
+#[pyo3(signature = (rate, per, nper, pv, fv = None, when = None))]
+#[allow(clippy::too_many_arguments)]
+fn ipmt(
+    py: Python<'_>,
+    rate: &Bound<'_, PyAny>,
+    per: &Bound<'_, PyAny>,
+    nper: &Bound<'_, PyAny>,
+    pv: &Bound<'_, PyAny>,
+    fv: Option<&Bound<'_, PyAny>>,
+    when: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let args = [
+        array(rate)?,
+        array(per)?,
+        array(nper)?,
+        array(pv)?,
+        optional(fv)?,
+        when_array(when)?,
+    ];
+    broadcast(py, args, |[rate, per, nper, pv, fv, w]| {
+        Ok(interest(rate, per, nper, pv, fv, self::when(w)?))
+    })
+}
+
+/// `ppmt(rate, per, nper, pv, fv=0, when='end')`
+#[pyfunction]
+#[pyo3(signature = (rate, per, nper, pv, fv = None, when = None))]
+#[allow(clippy::too_many_arguments)]
+fn ppmt(
+    py: Python<'_>,
+    rate: &Bound<'_, PyAny>,
+    per: &Bound<'_, PyAny>,
+    nper: &Bound<'_, PyAny>,
+    pv: &Bound<'_, PyAny>,
+    fv: Option<&Bound<'_, PyAny>>,
+    when: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let args = [
+        array(rate)?,
+        array(per)?,
+        array(nper)?,
+        array(pv)?,
+        optional(fv)?,
+        when_array(when)?,
+    ];
+    broadcast(py, args, |[rate, per, nper, pv, fv, w]| {
+        let when = self::when(w)?;
+        Ok(payment(rate, nper, pv, fv, when.clone()) - interest(rate, per, nper, pv, fv, when))
+    })
+}
+
+/// `rate(nper, pmt, pv, fv, when='end', guess=None, tol=None, maxiter=100, *, raise_exceptions=False)`
+#[pyfunction]
+#[pyo3(signature = (nper, pmt, pv, fv, when = None, guess = None, tol = None, maxiter = 100, *, raise_exceptions = false))]
+#[allow(clippy::too_many_arguments)]
+fn rate(
+    py: Python<'_>,
+    nper: &Bound<'_, PyAny>,
+    pmt: &Bound<'_, PyAny>,
+    pv: &Bound<'_, PyAny>,
+    fv: &Bound<'_, PyAny>,
+    when: Option<&Bound<'_, PyAny>>,
+    guess: Option<f64>,
+    tol: Option<f64>,
+    maxiter: u32,
+    raise_exceptions: bool,
+) -> PyResult<Py<PyAny>> {
+    let (guess, tol) = (guess.unwrap_or(0.1), tol.unwrap_or(1e-6));
+    let args = [
+        array(nper)?,
+        array(pmt)?,
+        array(pv)?,
+        array(fv)?,
+        when_array(when)?,
+    ];
+    broadcast(py, args, |[nper, pmt, pv, fv, w]| {
+        let when = self::when(w)?;
+        // Newton's method of `Rate` with a fractional `nper`
+        let mut rn = guess;
+        for _ in 0..maxiter {
+            let rnp1 = rn - Rate::_g_div_gp(rn, nper, pmt, pv, fv, when.clone());
+            let close = (rnp1 - rn).abs() < tol;
+            rn = rnp1;
+            if close {
+                return Ok(rn);
+            }
+        }
+        solution(None, raise_exceptions)
+    })
+}
+
+/// `irr(values, *, raise_exceptions=False)`, along the last axis of `values`
+#[pyfunction]
+#[pyo3(signature = (values, *, raise_exceptions = false))]
+fn irr(py: Python<'_>, values: &Bound<'_, PyAny>, raise_exceptions: bool) -> PyResult<Py<PyAny>> {
+    along_last_axis(py, values, |values| {
+        let res = InternalRateReturn::from_vec(values)
+            .get()
+            .map_err(value_error)?;
+        solution(res, raise_exceptions)
+    })
+}
+
+/// `npv(rate, values)`, with a result of shape `rate.shape + values.shape[:-1]`
+#[pyfunction]
+fn npv(py: Python<'_>, rate: &Bound<'_, PyAny>, values: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+    let rates = array(rate)?;
+    let values = array(values)?;
+    if values.ndim() == 0 {
+        return Err(PyValueError::new_err(
+            "`values` must be an array of cash flows",
+        ));
+    }
+    let last = Axis(values.ndim() - 1);
+    // copy each lane once, then borrow it for every rate
+    let lanes: Vec<Vec<f64>> = values.lanes(last).into_iter().map(|l| l.to_vec()).collect();
+    let mut out = Vec::new();
+    for &rate in rates.iter() {
+        for lane in &lanes {
+            let npv = NetPresentValue::from_slice((lane, rate)).get();
+            out.push(npv.map_err(value_error)?);
+        }
+    }
+    let shape = [rates.shape(), &values.shape()[..values.ndim() - 1]].concat();
+    let out = ArrayD::from_shape_vec(IxDyn(&shape), out)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(output(py, out))
+}
+
+/// `mirr(values, finance_rate, reinvest_rate, *, raise_exceptions=False)`, along the last axis of `values`
+#[pyfunction]
+#[pyo3(signature = (values, finance_rate, reinvest_rate, *, raise_exceptions = false))]
+fn mirr(
+    py: Python<'_>,
+    values: &Bound<'_, PyAny>,
+    finance_rate: f64,
+    reinvest_rate: f64,
+    raise_exceptions: bool,
+) -> PyResult<Py<PyAny>> {
+    along_last_axis(py, values, |values| {
+        let tup = (values, finance_rate, reinvest_rate);
+        let res = ModifiedIRR::from_tuple(tup).get().map_err(value_error)?;
+        solution(res, raise_exceptions)
+    })
+}
+
+/// The `rfinancial` Python module
+#[pymodule]
+fn rfinancial(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(fv, m)?)?;
+    m.add_function(wrap_pyfunction!(pv, m)?)?;
+    m.add_function(wrap_pyfunction!(pmt, m)?)?;
+    m.add_function(wrap_pyfunction!(nper, m)?)?;
+    m.add_function(wrap_pyfunction!(ipmt, m)?)?;
+    m.add_function(wrap_pyfunction!(ppmt, m)?)?;
+    m.add_function(wrap_pyfunction!(rate, m)?)?;
+    m.add_function(wrap_pyfunction!(irr, m)?)?;
+    m.add_function(wrap_pyfunction!(npv, m)?)?;
+    m.add_function(wrap_pyfunction!(mirr, m)?)?;
+    Ok(())
+}
+
+#[allow(unused_imports)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    /// Call `f` with scalar arguments and extract the float it returns
+    fn call<const N: usize>(
+        args: [f64; N],
+        f: impl Fn(Python<'_>, [Bound<'_, PyAny>; N]) -> PyResult<Py<PyAny>>,
+    ) -> PyResult<f64> {
+        Python::initialize();
+        Python::attach(|py| {
+            let args = args.map(|v| PyFloat::new(py, v).into_any());
+            f(py, args)?.extract::<f64>(py)
+        })
+    }
+
+    #[test]
+    fn test_python_broadcast_shape() {
+        assert_eq!(broadcast_shape(&[]).unwrap(), Vec::<usize>::new());
+        assert_eq!(broadcast_shape(&[&[], &[3]]).unwrap(), vec![3]);
+        assert_eq!(broadcast_shape(&[&[2, 1], &[3]]).unwrap(), vec![2, 3]);
+        assert_eq!(
+            broadcast_shape(&[&[4, 1, 3], &[2, 1], &[1]]).unwrap(),
+            vec![4, 2, 3]
+        );
+        assert!(broadcast_shape(&[&[2], &[3]]).is_err());
+        assert!(broadcast_shape(&[&[2, 3], &[3, 3]]).is_err());
+    }
+
+    #[test]
+    fn test_python_when_array() {
+        Python::initialize();
+        Python::attach(|py| {
+            let when = |obj: Option<&Bound<'_, PyAny>>| {
+                when_array(obj).map(|arr| arr.into_iter().collect::<Vec<f64>>())
+            };
+            assert_eq!(when(None).unwrap(), vec![0.0]);
+            for (s, tgt) in [
+                ("end", 0.0),
+                ("finish", 0.0),
+                ("begin", 1.0),
+                ("start", 1.0),
+            ] {
+                let obj = PyString::new(py, s).into_any();
+                assert_eq!(when(Some(&obj)).unwrap(), vec![tgt]);
+            }
+            let obj = PyFloat::new(py, 1.0).into_any();
+            assert_eq!(when(Some(&obj)).unwrap(), vec![1.0]);
+            let obj = PyString::new(py, "middle").into_any();
+            assert!(when(Some(&obj)).is_err());
+        });
+        assert_eq!(self::when(0.0).unwrap(), WhenType::End);
+        assert_eq!(self::when(1.0).unwrap(), WhenType::Begin);
+        assert!(self::when(0.5).is_err());
+    }
+
+    #[test]
+    fn test_python_tvm() {
+        // the closed forms may differ from the calculators in the last digit
+        let check = |res: f64, tgt: f64| {
+            assert!(
+                float_close(res, tgt, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            )
+        };
+        let res = call([0.075, 20.0, -2000.0, 0.0], |py, [r, n, p, v]| {
+            fv(py, &r, &n, &p, &v, None)
+        })
+        .unwrap();
+        let tgt = FutureValue::from_tuple((0.075, 20, -2000.0, 0.0, WhenType::End))
+            .get()
+            .unwrap();
+        check(res, tgt);
+
+        let res = call(
+            [0.05 / 12.0, 120.0, -100.0, 15692.93],
+            |py, [r, n, p, f]| pv(py, &r, &n, &p, Some(&f), None),
+        )
+        .unwrap();
+        let tgt = PresentValue::from_tuple((0.05 / 12.0, 120, -100.0, 15692.93, WhenType::End))
+            .get()
+            .unwrap();
+        check(res, tgt);
+
+        let res = call([0.075 / 12.0, 180.0, 200000.0, 1.0], |py, [r, n, v, w]| {
+            pmt(py, &r, &n, &v, None, Some(&w))
+        })
+        .unwrap();
+        let tgt = Payment::from_tuple((0.075 / 12.0, 180, 200000.0, 0.0, WhenType::Begin))
+            .get()
+            .unwrap();
+        check(res, tgt);
+
+        let res = call([0.07 / 12.0, -150.0, 8000.0], |py, [r, p, v]| {
+            nper(py, &r, &p, &v, None, None)
+        })
+        .unwrap();
+        let tgt = NumberPeriod::from_tuple((0.07 / 12.0, -150.0, 8000.0, 0.0, WhenType::End))
+            .get()
+            .unwrap()
+            .unwrap();
+        check(res, tgt);
+
+        let res = call([0.1 / 12.0, 1.0, 24.0, 2000.0], |py, [r, p, n, v]| {
+            ipmt(py, &r, &p, &n, &v, None, None)
+        })
+        .unwrap();
+        let tgt = InterestPayment::from_tuple((0.1 / 12.0, 1, 24, 2000.0, 0.0, WhenType::End))
+            .get()
+            .unwrap()
+            .unwrap();
+        check(res, tgt);
+
+        let res = call([0.1 / 12.0, 1.0, 24.0, 2000.0], |py, [r, p, n, v]| {
+            ppmt(py, &r, &p, &n, &v, None, None)
+        })
+        .unwrap();
+        let tgt = PrincipalPayment::from_tuple((0.1 / 12.0, 1, 24, 2000.0, 0.0, WhenType::End))
+            .get()
+            .unwrap()
+            .unwrap();
+        check(res, tgt);
+
+        let res = call([10.0, 0.0, -3500.0, 10000.0], |py, [n, p, v, f]| {
+            rate(py, &n, &p, &v, &f, None, None, None, 100, false)
+        })
+        .unwrap();
+        let tup = (10, 0.0, -3500.0, 10000.0, WhenType::End, 0.1, 1e-6, 100);
+        let tgt = Rate::from_tuple(tup).get().unwrap().unwrap();
+        check(res, tgt);
+    }
+
+    #[test]
+    fn test_python_fractional() {
+        let cases: [(f64, f64); 5] = [
+            // npf.fv(0.075, 20.5, -2000, 0)
+            (
+                call([0.075, 20.5, -2000.0, 0.0], |py, [r, n, p, v]| {
+                    fv(py, &r, &n, &p, &v, None)
+                })
+                .unwrap(),
+                90780.42029036922,
+            ),
+            // npf.pv(0.05/12, -12, -100, 15692.93)
+            (
+                call(
+                    [0.05 / 12.0, -12.0, -100.0, 15692.93],
+                    |py, [r, n, p, f]| pv(py, &r, &n, &p, Some(&f), None),
+                )
+                .unwrap(),
+                -17723.695631286777,
+            ),
+            // npf.pmt(0.075/12, 180.5, 200000, when='begin')
+            (
+                call([0.075 / 12.0, 180.5, 200000.0, 1.0], |py, [r, n, v, w]| {
+                    pmt(py, &r, &n, &v, None, Some(&w))
+                })
+                .unwrap(),
+                -1839.7438665934797,
+            ),
+            // npf.ipmt(0.1/12, 2, 24.5, 2000)
+            (
+                call([0.1 / 12.0, 2.0, 24.5, 2000.0], |py, [r, p, n, v]| {
+                    ipmt(py, &r, &p, &n, &v, None, None)
+                })
+                .unwrap(),
+                -16.050656826873166,
+            ),
+            // npf.ppmt(0.1/12, 2, 24.5, 2000)
+            (
+                call([0.1 / 12.0, 2.0, 24.5, 2000.0], |py, [r, p, n, v]| {
+                    ppmt(py, &r, &p, &n, &v, None, None)
+                })
+                .unwrap(),
+                -74.5371906150135,
+            ),
+        ];
+        for (res, tgt) in cases {
+            assert!(
+                float_close(res, tgt, RTOL, ATOL),
+                "{:#?} v.s. {:#?}",
+                res,
+                tgt
+            );
+        }
+
+        // the rate over 10.5 periods brings the future value to 0
+        let res = call([10.5, 0.0, -3500.0, 10000.0], |py, [n, p, v, f]| {
+            rate(py, &n, &p, &v, &f, None, None, None, 100, false)
+        })
+        .unwrap();
+        let tgt = future_value(res, 10.5, 0.0, -3500.0, WhenType::End);
+        assert!(
+            float_close(tgt, 10000.0, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            tgt,
+            10000.0
+        );
+    }
+
+    #[test]
+    fn test_python_errors() {
+        // ipmt of the 0th period has no solution
+        let res = call([0.01, 0.0, 24.0, 2000.0], |py, [r, p, n, v]| {
+            ipmt(py, &r, &p, &n, &v, None, None)
+        });
+        assert!(res.unwrap().is_nan());
+        // a cash flow must be an array
+        let res = call([0.1, 100.0], |py, [r, v]| npv(py, &r, &v));
+        assert!(res.is_err());
+    }
+}
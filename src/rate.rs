@@ -1,6 +1,10 @@
-use crate::{get_f64, get_u32, get_when, util::WhenType, Error, ParaMap, Result};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "std")]
+use crate::{get_f64, get_u32, get_when, Error, ParaMap};
+use crate::{util::WhenType, Result};
 /// # Compute the interest rate
-
+///
 /// ## Parameters
 /// * `nper` : number of compounding periods
 /// * `pmt` : payment in each period
@@ -21,8 +25,7 @@ use crate::{get_f64, get_u32, get_when, util::WhenType, Error, ParaMap, Result};
 /// println!("{:#?}'s rate is {:#?}", rate, rate.get());
 /// ```
 ///
-
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Rate {
     nper: u32,
     pmt: f64,
@@ -34,6 +37,22 @@ pub struct Rate {
     maxiter: u32,
 }
 
+impl Default for Rate {
+    /// Defaults of `numpy_financial`, i.e. `when=End`, `guess=0.1`, `tol=1e-6` and `maxiter=100`
+    fn default() -> Self {
+        Rate {
+            nper: 0,
+            pmt: 0.0,
+            pv: 0.0,
+            fv: 0.0,
+            when: WhenType::End,
+            guess: 0.1,
+            tol: 1e-6,
+            maxiter: 100,
+        }
+    }
+}
+
 impl Rate {
     /// Instantiate a `Rate` instance from a tuple of (`nper`, `pmt`, `pv`, `fv`, `when`, `guess`, `tol`, `maxiter`) in said order
     pub fn from_tuple(tup: (u32, f64, f64, f64, WhenType, f64, f64, u32)) -> Self {
@@ -51,6 +70,7 @@ impl Rate {
 
     /// Instantiate a `Rate` instance from a hash map with keys of (`nper`, `pmt`, `pv`, `fv`, `when`, `guess`, `tol`, `maxiter`) in said order
     /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
     pub fn from_map(map: ParaMap) -> Result<Self> {
         let op = |err: Error| {
             Error::OtherError(format!(
@@ -59,14 +79,14 @@ impl Rate {
             ))
         };
 
-        let nper = get_u32(&map, "nper").map_err(|err| op(err))?;
-        let pmt = get_f64(&map, "pmt").map_err(|err| op(err))?;
-        let pv = get_f64(&map, "pv").map_err(|err| op(err))?;
-        let fv = get_f64(&map, "fv").map_err(|err| op(err))?;
-        let when = get_when(&map, "when").map_err(|err| op(err))?;
-        let guess = get_f64(&map, "guess").map_err(|err| op(err))?;
-        let tol = get_f64(&map, "tol").map_err(|err| op(err))?;
-        let maxiter = get_u32(&map, "maxiter").map_err(|err| op(err))?;
+        let nper = get_u32(&map, "nper").map_err(&op)?;
+        let pmt = get_f64(&map, "pmt").map_err(&op)?;
+        let pv = get_f64(&map, "pv").map_err(&op)?;
+        let fv = get_f64(&map, "fv").map_err(&op)?;
+        let when = get_when(&map, "when").map_err(&op)?;
+        let guess = get_f64(&map, "guess").map_err(&op)?;
+        let tol = get_f64(&map, "tol").map_err(&op)?;
+        let maxiter = get_u32(&map, "maxiter").map_err(op)?;
         Ok(Rate {
             nper,
             pmt,
@@ -79,25 +99,26 @@ impl Rate {
         })
     }
 
-    /// Evaluate `g(r_n)/g'(r_n)`, where `g = fv + pv*(1+rate)**nper + pmt*(1+rate*when)/rate * ((1+rate)**nper - 1)`
-    fn _g_div_gp(r: f64, n: u32, p: f64, x: f64, y: f64, w: WhenType) -> f64 {
-        // converts to f64 for calculation
-        let n = n as f64;
-        let w = w as u8 as f64;
+    /// Evaluate `g(r_n)/g'(r_n)`, where `g = fv + pv*(1+rate)**nper + pmt*(1+rate)**offset/rate * ((1+rate)**nper - 1)`
+    /// * `n` is a `f64` for spreadsheet functions, which accept a fractional `nper`
+    pub(crate) fn _g_div_gp(r: f64, n: f64, p: f64, x: f64, y: f64, w: WhenType) -> f64 {
+        // growth of a payment in its period and its derivative, i.e. `1 + r*when` and `when` for `End` and `Begin`
+        let f = w.growth(r);
+        let fp = w.offset() * f / (r + 1.0);
 
         let t1 = (r + 1.0).powf(n);
         let t2 = (r + 1.0).powf(n - 1.0);
-        let g = y + t1 * x + p * (t1 - 1.0) * (r * w + 1.0) / r;
-        let gp = n * t2 * x - p * (t1 - 1.0) * (r * w + 1.0) / (r.powf(2.0))
-            + n * p * t2 * (r * w + 1.0) / r
-            + p * (t1 - 1.0) * w / r;
+        let g = y + t1 * x + p * (t1 - 1.0) * f / r;
+        let gp = n * t2 * x - p * (t1 - 1.0) * f / (r.powf(2.0))
+            + n * p * t2 * f / r
+            + p * (t1 - 1.0) * fp / r;
         g / gp
     }
 
     fn rate(&self) -> Result<Option<f64>> {
         /*
            The rate of interest is computed by iteratively solving the (non-linear) equation:
-           `fv + pv*(1+rate)**nper + pmt*(1+rate*when)/rate * ((1+rate)**nper - 1) = 0` for `rate`
+           `fv + pv*(1+rate)**nper + pmt*(1+rate)**offset/rate * ((1+rate)**nper - 1) = 0` for `rate`
         */
         // Assume all parameters are provided - deal with default arguments later
 
@@ -116,8 +137,9 @@ impl Rate {
         let mut close = false;
 
         while (iter < self.maxiter) & (!close) {
+            let nper = self.nper as f64;
             let rnp1 =
-                rn - Self::_g_div_gp(rn, self.nper, self.pmt, self.pv, self.fv, self.when.clone());
+                rn - Self::_g_div_gp(rn, nper, self.pmt, self.pv, self.fv, self.when.clone());
             let diff = (rnp1 - rn).abs();
             close = diff < self.tol;
             iter += 1;
@@ -126,12 +148,14 @@ impl Rate {
 
         // if convergence
         if close {
+            #[cfg(feature = "std")]
             println!("Converged - {}, at: {}", rn, iter);
-            return Ok(Some(rn));
+            Ok(Some(rn))
         // if no convergence after maxiter
         } else {
+            #[cfg(feature = "std")]
             println!("Maximum iterations reached - {}, at: {}", self.maxiter, rn);
-            return Ok(None);
+            Ok(None)
         }
     }
 
@@ -161,6 +185,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_rate_from_map() {
         let mut map = ParaMap::new();
@@ -282,6 +307,7 @@ mod tests {
         assert_eq!(res, tgt, "{:#?} v.s. {:#?}", res, tgt);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_rate_err() {
         let mut map = ParaMap::new();
@@ -296,4 +322,37 @@ mod tests {
         let rate = Rate::from_map(map);
         assert!(rate.is_err());
     }
+
+    #[test]
+    fn test_rate_with_middle() {
+        let tup = (20, -2000.0, 0.0, 89798.49848593227, WhenType::Middle);
+        let rate = Rate::from_tuple((tup.0, tup.1, tup.2, tup.3, tup.4, 0.1, 1e-6, 100));
+        let res = rate.get().unwrap().unwrap();
+        let tgt = 0.075;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+
+        // a quarter of a period before the end, between the end and the begining
+        let rate = Rate::from_tuple((
+            20,
+            -2000.0,
+            0.0,
+            89798.49848593227,
+            WhenType::from_offset(0.25).unwrap(),
+            0.1,
+            1e-6,
+            100,
+        ));
+        let res = rate.get().unwrap().unwrap();
+        assert!(res > 0.075, "{:#?}", res);
+        let tgt =
+            FutureValue::from_tuple((res, 20, -2000.0, 0.0, WhenType::from_offset(0.25).unwrap()))
+                .get()
+                .unwrap();
+        assert!(float_close(89798.49848593227, tgt, 1e-6, 0.0), "{:#?}", tgt);
+    }
 }
@@ -0,0 +1,324 @@
+use crate::*;
+
+/// # Kind of a parameter, i.e. the variant of [`ParaType`] it expects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParaKind {
+    F64,
+    U32,
+    When,
+    VecF64,
+}
+
+/// # Description of one parameter of a function
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParaSpec {
+    /// key in the [`ParaMap`]
+    pub name: &'static str,
+    /// expected variant of [`ParaType`]
+    pub kind: ParaKind,
+    /// value used when the key is missing, or `None` if the parameter is required
+    pub default: Option<ParaType>,
+}
+
+/// # Output of a function evaluated by name
+#[derive(Clone, Debug, PartialEq)]
+pub enum CalcResult {
+    /// e.g. `fv`, `pv`, `pmt` and `npv`
+    F64(f64),
+    /// e.g. `nper`, `ipmt`, `rate` and `irr` which may have no solution
+    OptionF64(Option<f64>),
+    /// e.g. `arm`
+    Schedule(Vec<AmortizationRow>),
+}
+
+impl From<f64> for CalcResult {
+    fn from(v: f64) -> Self {
+        CalcResult::F64(v)
+    }
+}
+
+impl From<Option<f64>> for CalcResult {
+    fn from(v: Option<f64>) -> Self {
+        CalcResult::OptionF64(v)
+    }
+}
+
+impl From<Vec<AmortizationRow>> for CalcResult {
+    fn from(v: Vec<AmortizationRow>) -> Self {
+        CalcResult::Schedule(v)
+    }
+}
+
+fn run<C>(map: ParaMap) -> Result<CalcResult>
+where
+    C: Calculator,
+    C::Output: Into<CalcResult>,
+{
+    Ok(C::from_params(map)?.compute()?.into())
+}
+
+fn required(name: &'static str, kind: ParaKind) -> ParaSpec {
+    ParaSpec {
+        name,
+        kind,
+        default: None,
+    }
+}
+
+fn optional(name: &'static str, kind: ParaKind, default: ParaType) -> ParaSpec {
+    ParaSpec {
+        name,
+        kind,
+        default: Some(default),
+    }
+}
+
+/// Names of all functions that can be evaluated by [`evaluate`]
+pub const FUNCTIONS: [&str; 21] = [
+    "fv",
+    "pv",
+    "pmt",
+    "nper",
+    "ipmt",
+    "ppmt",
+    "rate",
+    "irr",
+    "npv",
+    "mirr",
+    "arm",
+    "fvschedule",
+    "pvschedule",
+    "npvschedule",
+    "fvstream",
+    "payback",
+    "discounted_payback",
+    "pi",
+    "bcr",
+    "eaa",
+    "eac",
+];
+
+/// Get the parameters of function `name`, in the order of its `from_tuple`
+pub fn parameters(name: &str) -> Result<Vec<ParaSpec>> {
+    use ParaKind::*;
+    let fv = || optional("fv", F64, ParaType::F64(0.0));
+    let when = || optional("when", When, ParaType::When(WhenType::End));
+    let values_rate = || vec![required("values", VecF64), required("rate", F64)];
+
+    let params = match name {
+        "fv" => vec![
+            required("rate", F64),
+            required("nper", U32),
+            required("pmt", F64),
+            required("pv", F64),
+            when(),
+        ],
+        "pv" => vec![
+            required("rate", F64),
+            required("nper", U32),
+            required("pmt", F64),
+            fv(),
+            when(),
+        ],
+        "pmt" => vec![
+            required("rate", F64),
+            required("nper", U32),
+            required("pv", F64),
+            fv(),
+            when(),
+        ],
+        "nper" => vec![
+            required("rate", F64),
+            required("pmt", F64),
+            required("pv", F64),
+            fv(),
+            when(),
+        ],
+        "ipmt" | "ppmt" => vec![
+            required("rate", F64),
+            required("per", U32),
+            required("nper", U32),
+            required("pv", F64),
+            fv(),
+            when(),
+        ],
+        "rate" => vec![
+            required("nper", U32),
+            required("pmt", F64),
+            required("pv", F64),
+            required("fv", F64),
+            when(),
+            optional("guess", F64, ParaType::F64(0.1)),
+            optional("tol", F64, ParaType::F64(1e-6)),
+            optional("maxiter", U32, ParaType::U32(100)),
+        ],
+        "irr" | "payback" => vec![required("values", VecF64)],
+        "npv" => vec![required("values", VecF64), required("rate", F64), when()],
+        "discounted_payback" | "pi" | "bcr" | "eaa" | "eac" => values_rate(),
+        "mirr" => vec![
+            required("values", VecF64),
+            required("finance_rate", F64),
+            required("reinvest_rate", F64),
+        ],
+        "arm" => vec![
+            required("rate", F64),
+            required("nper", U32),
+            required("pv", F64),
+            fv(),
+            when(),
+            required("first_reset", U32),
+            required("reset_freq", U32),
+            required("index", VecF64),
+            optional("margin", F64, ParaType::F64(0.0)),
+            optional("periodic_cap", F64, ParaType::F64(f64::INFINITY)),
+            optional("lifetime_cap", F64, ParaType::F64(f64::INFINITY)),
+            optional("lifetime_floor", F64, ParaType::F64(f64::NEG_INFINITY)),
+        ],
+        "fvschedule" => vec![
+            required("rates", VecF64),
+            required("pmt", F64),
+            required("pv", F64),
+            when(),
+        ],
+        "pvschedule" => vec![
+            required("rates", VecF64),
+            required("pmt", F64),
+            fv(),
+            when(),
+        ],
+        "npvschedule" => vec![required("values", VecF64), required("rates", VecF64)],
+        "fvstream" => vec![
+            required("rate", F64),
+            required("pmts", VecF64),
+            required("pv", F64),
+            when(),
+        ],
+        _ => return Err(Error::ParaError(format!("{}: unknown function", name))),
+    };
+
+    Ok(params)
+}
+
+/// Evaluate function `name` from a [`ParaMap`], filling in defaults of missing optional parameters
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let mut map = ParaMap::new();
+/// map.insert("rate".into(), ParaType::F64(0.08 / 12.0));
+/// map.insert("nper".into(), ParaType::U32(60));
+/// map.insert("pv".into(), ParaType::F64(15000.0));
+/// let pmt = evaluate("pmt", map);
+/// println!("pmt is {:?}", pmt);
+/// ```
+pub fn evaluate(name: &str, mut map: ParaMap) -> Result<CalcResult> {
+    for spec in parameters(name)? {
+        if let Some(default) = spec.default {
+            map.entry(spec.name.to_string()).or_insert(default);
+        }
+    }
+
+    match name {
+        "fv" => run::<FutureValue>(map),
+        "pv" => run::<PresentValue>(map),
+        "pmt" => run::<Payment>(map),
+        "nper" => run::<NumberPeriod>(map),
+        "ipmt" => run::<InterestPayment>(map),
+        "ppmt" => run::<PrincipalPayment>(map),
+        "rate" => run::<Rate>(map),
+        "irr" => run::<InternalRateReturn>(map),
+        "npv" => run::<NetPresentValue>(map),
+        "mirr" => run::<ModifiedIRR>(map),
+        "arm" => run::<AdjustableRateLoan>(map),
+        "fvschedule" => run::<FutureValueSchedule>(map),
+        "pvschedule" => run::<PresentValueSchedule>(map),
+        "npvschedule" => run::<NetPresentValueSchedule>(map),
+        "fvstream" => run::<FutureValueStream>(map),
+        "payback" => run::<PaybackPeriod>(map),
+        "discounted_payback" => run::<DiscountedPaybackPeriod>(map),
+        "pi" => run::<ProfitabilityIndex>(map),
+        "bcr" => run::<BenefitCostRatio>(map),
+        "eaa" => run::<EquivalentAnnualAnnuity>(map),
+        "eac" => run::<EquivalentAnnualCost>(map),
+        _ => Err(Error::ParaError(format!("{}: unknown function", name))),
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_registry_evaluate() {
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.08 / 12.0));
+        map.insert("nper".into(), ParaType::U32(60));
+        map.insert("pv".into(), ParaType::F64(15000.0));
+        // res = npf.pmt(0.08 / 12, 5 * 12, 15000)
+        // tgt = -304.145914
+        match evaluate("pmt", map).unwrap() {
+            CalcResult::F64(res) => {
+                assert!(float_close(res, -304.145914, RTOL, ATOL), "{:#?}", res)
+            }
+            res => panic!("{:#?}", res),
+        }
+
+        let mut map = ParaMap::new();
+        map.insert("nper".into(), ParaType::U32(10));
+        map.insert("pmt".into(), ParaType::F64(0.0));
+        map.insert("pv".into(), ParaType::F64(-3500.0));
+        map.insert("fv".into(), ParaType::F64(10000.0));
+        // npf.rate(10, 0, -3500, 10000)
+        // 0.11069085371426901
+        match evaluate("rate", map).unwrap() {
+            CalcResult::OptionF64(Some(res)) => {
+                assert!(
+                    float_close(res, 0.11069085371426901, RTOL, ATOL),
+                    "{:#?}",
+                    res
+                )
+            }
+            res => panic!("{:#?}", res),
+        }
+    }
+
+    #[test]
+    fn test_registry_parameters() {
+        // every function is described and can be evaluated from its own parameters
+        for name in FUNCTIONS {
+            let params = parameters(name).unwrap();
+            assert!(!params.is_empty(), "{}", name);
+
+            let mut map = ParaMap::new();
+            for spec in params.iter().filter(|spec| spec.default.is_none()) {
+                let value = match spec.kind {
+                    ParaKind::F64 => ParaType::F64(0.05),
+                    ParaKind::U32 => ParaType::U32(2),
+                    ParaKind::When => ParaType::When(WhenType::End),
+                    ParaKind::VecF64 => ParaType::VecF64(vec![-100.0, 60.0, 60.0]),
+                };
+                map.insert(spec.name.to_string(), value);
+            }
+            assert!(evaluate(name, map).is_ok(), "{}", name);
+        }
+
+        let params = parameters("pmt").unwrap();
+        let names: Vec<&str> = params.iter().map(|spec| spec.name).collect();
+        assert_eq!(names, vec!["rate", "nper", "pv", "fv", "when"]);
+        assert_eq!(params[3].default, Some(ParaType::F64(0.0)));
+        assert_eq!(params[0].kind, ParaKind::F64);
+    }
+
+    #[test]
+    fn test_registry_err() {
+        assert!(parameters("xnpv").is_err());
+        assert!(evaluate("xnpv", ParaMap::new()).is_err());
+
+        // a required parameter is missing
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.08 / 12.0));
+        map.insert("pv".into(), ParaType::F64(15000.0));
+        assert!(evaluate("pmt", map).is_err());
+    }
+}
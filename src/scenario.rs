@@ -0,0 +1,332 @@
+use crate::{Error, ParaMap, ParaType, Result, WhenType};
+
+/// # A change to one parameter of a [`ParaMap`]
+/// It applies to `f64`, `u32`, `i64` and vectors of `f64` alike; a `u32` or `i64` must stay a whole number in range
+#[derive(Clone, Debug, PartialEq)]
+pub enum Override {
+    /// replace the value
+    Set(f64),
+    /// add to the value, e.g. `Add(0.01)` for `+100bp`
+    Add(f64),
+    /// multiply the value
+    Scale(f64),
+}
+
+impl Override {
+    /// Parse an override from text: `240` sets, `+100bp`, `-0.5` or `+12` adds, and `*1.1` scales
+    pub fn parse(text: &str) -> Result<Self> {
+        let err = || Error::ParaError(format!("{}: override", text));
+        let text = text.trim();
+        let number = |s: &str| s.trim().parse::<f64>().map_err(|_| err());
+        if let Some(s) = text.strip_prefix('*') {
+            return Ok(Override::Scale(number(s)?));
+        }
+        let (body, unit) = match text.strip_suffix("bp") {
+            Some(body) => (body, 1e-4),
+            None => (text, 1.0),
+        };
+        if body.starts_with('+') || body.starts_with('-') {
+            Ok(Override::Add(number(body)? * unit))
+        } else if unit == 1.0 {
+            Ok(Override::Set(number(body)?))
+        } else {
+            Err(err())
+        }
+    }
+
+    fn value(&self, v: f64) -> f64 {
+        match self {
+            Override::Set(x) => *x,
+            Override::Add(x) => v + x,
+            Override::Scale(x) => v * x,
+        }
+    }
+
+    /// Apply the override to `field` of `map`
+    pub fn apply(&self, map: &mut ParaMap, field: &str) -> Result<()> {
+        let err = |expect: &str| Error::ParaError(format!("{}: {}", field, expect));
+        let new = match map.get(field) {
+            Some(ParaType::F64(v)) => ParaType::F64(self.value(*v)),
+            Some(ParaType::U32(v)) => {
+                let x = self.value(*v as f64);
+                if x < 0.0 || x > u32::MAX as f64 || x.fract() != 0.0 {
+                    return Err(err("u32"));
+                }
+                ParaType::U32(x as u32)
+            }
+            Some(ParaType::I64(v)) => {
+                let x = self.value(*v as f64);
+                if x < i64::MIN as f64 || x >= i64::MAX as f64 || x.fract() != 0.0 {
+                    return Err(err("i64"));
+                }
+                ParaType::I64(x as i64)
+            }
+            Some(ParaType::When(v)) => {
+                let x = self.value(v.offset());
+                ParaType::When(WhenType::from_offset(x).ok_or_else(|| err("WhenType"))?)
+            }
+            Some(ParaType::VecF64(v)) => match self {
+                Override::Set(_) => return Err(err("VecF64")),
+                _ => ParaType::VecF64(v.iter().map(|&c| self.value(c)).collect()),
+            },
+            Some(_) => return Err(err("f64, u32, i64, WhenType or VecF64")),
+            None => return Err(err("missing")),
+        };
+        map.insert(field.to_string(), new);
+        Ok(())
+    }
+}
+
+/// # A named set of overrides
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    name: String,
+    overrides: Vec<(String, Override)>,
+}
+
+impl Scenario {
+    /// Instantiate a `Scenario` instance from a tuple of (`name`, `overrides`) in said order
+    pub fn from_tuple(tup: (String, Vec<(String, Override)>)) -> Self {
+        Scenario {
+            name: tup.0,
+            overrides: tup.1,
+        }
+    }
+
+    /// Parse a `Scenario` from comma-separated `field override`, e.g. `rate +100bp, nper 240`, see [`Override::parse`]
+    pub fn parse(name: &str, text: &str) -> Result<Self> {
+        let overrides = text
+            .split(',')
+            .filter(|clause| !clause.trim().is_empty())
+            .map(
+                |clause| match clause.trim().split_once(char::is_whitespace) {
+                    Some((field, ov)) => Ok((field.trim().to_string(), Override::parse(ov)?)),
+                    None => Err(Error::ParaError(format!("{}: field override", clause))),
+                },
+            )
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Scenario {
+            name: name.to_string(),
+            overrides,
+        })
+    }
+
+    /// Apply every override to a copy of `map`
+    pub fn apply(&self, map: &ParaMap) -> Result<ParaMap> {
+        let mut map = map.clone();
+        for (field, ov) in self.overrides.iter() {
+            ov.apply(&mut map, field)?;
+        }
+        Ok(map)
+    }
+}
+
+/// Result of one scenario
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioRow {
+    /// name of the scenario, `base` for the base case
+    pub name: String,
+    /// output of the calculator
+    pub value: f64,
+    /// change of output from the base case
+    pub change: f64,
+}
+
+/// One bar of a tornado chart
+#[derive(Clone, Debug, PartialEq)]
+pub struct TornadoBar {
+    /// the parameter shocked
+    pub field: String,
+    /// output with the low shock
+    pub low: f64,
+    /// output with the high shock
+    pub high: f64,
+    /// `|high - low|`, which bars are sorted by
+    pub swing: f64,
+}
+
+/// # Evaluate a calculator under a set of scenarios
+///
+/// ## Parameters
+/// * `map` : the base parameters of the calculator
+/// * `scenarios` : the [`Scenario`]s to compare with the base case
+///
+/// ## Return:
+/// * `rows`: a [`ScenarioRow`] for the base case followed by one for each scenario
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let mut map = ParaMap::new();
+/// map.insert("rate".into(), ParaType::F64(0.05));
+/// map.insert("nper".into(), ParaType::U32(30));
+/// map.insert("pv".into(), ParaType::F64(250000.0));
+/// map.insert("fv".into(), ParaType::F64(0.0));
+/// map.insert("when".into(), ParaType::When(WhenType::End));
+/// let scenarios = vec![
+///     Scenario::parse("rate +100bp", "rate +100bp").unwrap(),
+///     Scenario::parse("nper 20", "nper 20").unwrap(),
+/// ];
+/// let analysis = ScenarioAnalysis::from_tuple((map, scenarios));
+/// println!("{:#?}", analysis.get(|map| Payment::from_map(map)?.get()));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioAnalysis {
+    map: ParaMap,
+    scenarios: Vec<Scenario>,
+}
+
+impl ScenarioAnalysis {
+    /// Instantiate a `ScenarioAnalysis` instance from a tuple of (`map`, `scenarios`) in said order
+    pub fn from_tuple(tup: (ParaMap, Vec<Scenario>)) -> Self {
+        ScenarioAnalysis {
+            map: tup.0,
+            scenarios: tup.1,
+        }
+    }
+
+    /// Get the result of each scenario from an instance of `ScenarioAnalysis`, where `calc` computes the output from a
+    /// [`ParaMap`]
+    pub fn get<F>(&self, calc: F) -> Result<Vec<ScenarioRow>>
+    where
+        F: Fn(ParaMap) -> Result<f64>,
+    {
+        let base = calc(self.map.clone())?;
+        let mut rows = vec![ScenarioRow {
+            name: "base".into(),
+            value: base,
+            change: 0.0,
+        }];
+        for scenario in self.scenarios.iter() {
+            let value = calc(scenario.apply(&self.map)?)?;
+            rows.push(ScenarioRow {
+                name: scenario.name.clone(),
+                value,
+                change: value - base,
+            });
+        }
+        Ok(rows)
+    }
+
+    /// Get the tornado chart data from an instance of `ScenarioAnalysis`, shocking one parameter at a time with
+    /// (`field`, `low`, `high`) of `shocks`. Bars are sorted by swing, largest first
+    pub fn get_tornado<F>(
+        &self,
+        calc: F,
+        shocks: &[(String, Override, Override)],
+    ) -> Result<Vec<TornadoBar>>
+    where
+        F: Fn(ParaMap) -> Result<f64>,
+    {
+        let mut bars = Vec::with_capacity(shocks.len());
+        for (field, low, high) in shocks.iter() {
+            let mut map = self.map.clone();
+            low.apply(&mut map, field)?;
+            let low = calc(map)?;
+
+            let mut map = self.map.clone();
+            high.apply(&mut map, field)?;
+            let high = calc(map)?;
+
+            bars.push(TornadoBar {
+                field: field.clone(),
+                low,
+                high,
+                swing: (high - low).abs(),
+            });
+        }
+        bars.sort_by(|a, b| b.swing.total_cmp(&a.swing));
+        Ok(bars)
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    fn base() -> ParaMap {
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.08 / 12.0));
+        map.insert("nper".into(), ParaType::U32(60));
+        map.insert("pv".into(), ParaType::F64(15000.0));
+        map.insert("fv".into(), ParaType::F64(0.0));
+        map.insert("when".into(), ParaType::When(WhenType::End));
+        map
+    }
+
+    #[test]
+    fn test_override_parse() {
+        assert_eq!(Override::parse("240").unwrap(), Override::Set(240.0));
+        assert_eq!(Override::parse("+12").unwrap(), Override::Add(12.0));
+        assert_eq!(Override::parse("*1.1").unwrap(), Override::Scale(1.1));
+        match Override::parse("-50bp").unwrap() {
+            Override::Add(x) => assert!(float_close(x, -0.005, RTOL, ATOL)),
+            ov => panic!("{:?}", ov),
+        }
+        assert!(Override::parse("100bp").is_err());
+        assert!(Override::parse("abc").is_err());
+    }
+
+    #[test]
+    fn test_override_apply() {
+        let mut map = base();
+        Override::Add(-12.0).apply(&mut map, "nper").unwrap();
+        Override::Set(1.0).apply(&mut map, "when").unwrap();
+        assert_eq!(map.get("nper"), Some(&ParaType::U32(48)));
+        assert_eq!(map.get("when"), Some(&ParaType::When(WhenType::Begin)));
+        assert!(Override::Scale(0.3).apply(&mut map, "nper").is_err());
+        assert!(Override::Set(1.0).apply(&mut map, "guess").is_err());
+    }
+
+    #[test]
+    fn test_scenario_analysis() {
+        let scenarios = vec![
+            Scenario::parse("rate 0", "rate 0").unwrap(),
+            Scenario::parse("longer", "nper +60, pv *2").unwrap(),
+        ];
+        let analysis = ScenarioAnalysis::from_tuple((base(), scenarios));
+        let res = analysis.get(|map| Payment::from_map(map)?.get()).unwrap();
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].name, "base");
+
+        // res = npf.pmt(0.08 / 12, 5 * 12, 15000)
+        // tgt = -304.145914
+        assert!(float_close(res[0].value, -304.145914, RTOL, ATOL));
+        assert_eq!(res[1].value, -250.0);
+        assert!(float_close(res[1].change, -250.0 + 304.145914, RTOL, ATOL));
+
+        let tgt = Payment::from_tuple((0.08 / 12.0, 120, 30000.0, 0.0, WhenType::End))
+            .get()
+            .unwrap();
+        assert!(float_close(res[2].value, tgt, RTOL, ATOL));
+    }
+
+    #[test]
+    fn test_scenario_tornado() {
+        let analysis = ScenarioAnalysis::from_tuple((base(), vec![]));
+        let shocks = vec![
+            (
+                "rate".to_string(),
+                Override::Add(-0.0001),
+                Override::Add(0.0001),
+            ),
+            ("pv".to_string(), Override::Scale(0.5), Override::Scale(1.5)),
+        ];
+        let res = analysis
+            .get_tornado(|map| Payment::from_map(map)?.get(), &shocks)
+            .unwrap();
+        assert_eq!(res[0].field, "pv");
+        assert_eq!(res[1].field, "rate");
+        assert!(res[0].swing > res[1].swing);
+        assert!(float_close(res[0].swing, 304.145914, RTOL, ATOL));
+    }
+
+    #[test]
+    fn test_scenario_err() {
+        assert!(Scenario::parse("bad", "rate").is_err());
+        let scenarios = vec![Scenario::parse("typo", "Rate +100bp").unwrap()];
+        let analysis = ScenarioAnalysis::from_tuple((base(), scenarios));
+        assert!(analysis.get(|map| Payment::from_map(map)?.get()).is_err());
+    }
+}
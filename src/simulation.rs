@@ -0,0 +1,351 @@
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::{Error, InternalRateReturn, NetPresentValue, Result};
+use alloc::{format, vec::Vec};
+
+/// # A distribution to draw a cash flow or a rate from
+#[derive(Clone, Debug, PartialEq)]
+pub enum Distribution {
+    /// always the same value
+    Fixed(f64),
+    /// normal distribution of (`mean`, `std_dev`)
+    Normal(f64, f64),
+    /// lognormal distribution, i.e. `exp` of a normal distribution of (`mu`, `sigma`)
+    LogNormal(f64, f64),
+    /// triangular distribution of (`min`, `mode`, `max`)
+    Triangular(f64, f64, f64),
+    /// uniform distribution of (`min`, `max`)
+    Uniform(f64, f64),
+    /// each of the observed values with equal probability
+    Empirical(Vec<f64>),
+}
+
+/// A small self-contained random number generator (xoshiro256**), seeded through splitmix64
+#[derive(Clone, Debug)]
+struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let mut s = seed;
+        let mut next = || {
+            s = s.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = s;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+        Rng {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    /// uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// standard normal by Box-Muller
+    fn next_normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * core::f64::consts::PI * u2).cos()
+    }
+}
+
+impl Distribution {
+    fn check(&self) -> Result<()> {
+        let valid = match self {
+            Distribution::Fixed(_) => true,
+            Distribution::Normal(_, sd) | Distribution::LogNormal(_, sd) => *sd >= 0.0,
+            Distribution::Triangular(min, mode, max) => min <= mode && mode <= max,
+            Distribution::Uniform(min, max) => min <= max,
+            Distribution::Empirical(v) => !v.is_empty(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::ParaError(format!(
+                "{:?}: invalid distribution",
+                self
+            )))
+        }
+    }
+
+    fn sample(&self, rng: &mut Rng) -> f64 {
+        match self {
+            Distribution::Fixed(v) => *v,
+            Distribution::Normal(mean, sd) => mean + sd * rng.next_normal(),
+            Distribution::LogNormal(mu, sigma) => (mu + sigma * rng.next_normal()).exp(),
+            Distribution::Triangular(min, mode, max) => {
+                // inverse of the cumulative distribution
+                let u = rng.next_f64();
+                let range = max - min;
+                if range == 0.0 {
+                    *min
+                } else if u < (mode - min) / range {
+                    min + (u * range * (mode - min)).sqrt()
+                } else {
+                    max - ((1.0 - u) * range * (max - mode)).sqrt()
+                }
+            }
+            Distribution::Uniform(min, max) => min + (max - min) * rng.next_f64(),
+            Distribution::Empirical(v) => {
+                let i = (rng.next_f64() * v.len() as f64) as usize;
+                v[i.min(v.len() - 1)]
+            }
+        }
+    }
+}
+
+/// # Summary statistics of simulated outcomes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    /// outcomes in ascending order
+    pub samples: Vec<f64>,
+    /// mean of outcomes
+    pub mean: f64,
+    /// sample standard deviation of outcomes
+    pub std_dev: f64,
+}
+
+impl Summary {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let std_dev = if samples.len() > 1 {
+            (samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        Summary {
+            samples,
+            mean,
+            std_dev,
+        }
+    }
+
+    /// The `q`-th percentile, `q` in `[0, 100]`, linearly interpolated between outcomes, or `None` if there is none
+    pub fn percentile(&self, q: f64) -> Option<f64> {
+        if self.samples.is_empty() || !(0.0..=100.0).contains(&q) {
+            return None;
+        }
+        let pos = q / 100.0 * (self.samples.len() - 1) as f64;
+        let lo = pos.floor() as usize;
+        let hi = pos.ceil() as usize;
+        Some(self.samples[lo] + (self.samples[hi] - self.samples[lo]) * (pos - lo as f64))
+    }
+}
+
+/// # Outcome of a Monte Carlo simulation
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationResult {
+    /// npv of every trial
+    pub npv: Summary,
+    /// irr of every trial where it has a solution
+    pub irr: Summary,
+    /// probability of `npv < 0`
+    pub prob_negative_npv: f64,
+}
+
+/// # Simulate the net present value and internal rate of return of an uncertain cash flow
+///
+/// ## Parameters
+/// * `values` : a [`Distribution`] of the cash flow in each period, assume first payment is made at present,
+///   i.e. `t=0` the begining of 1st period
+/// * `rate` : a [`Distribution`] of the discount rate
+/// * `trials` : number of trials
+/// * `seed` : seed of the random number generator, so the same seed gives the same outcome
+///
+/// ## Return:
+/// * `result` : a [`SimulationResult`] with the distribution of `npv` and `irr`
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let values = vec![
+///     Distribution::Fixed(-1000.0),
+///     Distribution::Normal(400.0, 50.0),
+///     Distribution::Triangular(300.0, 450.0, 500.0),
+///     Distribution::Uniform(400.0, 600.0),
+/// ];
+/// let mc = MonteCarlo::from_tuple((values, Distribution::Fixed(0.08), 1000, 42));
+/// let res = mc.get().unwrap();
+/// println!("mean npv is {}, 5th percentile is {:?}", res.npv.mean, res.npv.percentile(5.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonteCarlo {
+    values: Vec<Distribution>,
+    rate: Distribution,
+    trials: u32,
+    seed: u64,
+}
+
+impl MonteCarlo {
+    /// Instantiate a `MonteCarlo` instance from a tuple of (`values`, `rate`, `trials` and `seed`) in said order
+    pub fn from_tuple(tup: (Vec<Distribution>, Distribution, u32, u64)) -> Self {
+        MonteCarlo {
+            values: tup.0,
+            rate: tup.1,
+            trials: tup.2,
+            seed: tup.3,
+        }
+    }
+
+    fn simulate(&self) -> Result<SimulationResult> {
+        if self.trials == 0 {
+            return Err(Error::ParaError("trials: must be positive".into()));
+        }
+        for d in self.values.iter() {
+            d.check()?;
+        }
+        self.rate.check()?;
+
+        let mut rng = Rng::new(self.seed);
+        let mut npvs = Vec::with_capacity(self.trials as usize);
+        let mut irrs = Vec::with_capacity(self.trials as usize);
+        for _ in 0..self.trials {
+            let values: Vec<f64> = self.values.iter().map(|d| d.sample(&mut rng)).collect();
+            let rate = self.rate.sample(&mut rng);
+            npvs.push(NetPresentValue::from_slice((&values, rate)).get()?);
+            if let Some(irr) = InternalRateReturn::from_slice(&values).get()? {
+                irrs.push(irr);
+            }
+        }
+
+        let negative = npvs.iter().filter(|&&npv| npv < 0.0).count();
+        Ok(SimulationResult {
+            prob_negative_npv: negative as f64 / self.trials as f64,
+            npv: Summary::from_samples(npvs),
+            irr: Summary::from_samples(irrs),
+        })
+    }
+
+    /// Get the outcome of the simulation from an instance of `MonteCarlo`
+    pub fn get(&self) -> Result<SimulationResult> {
+        self.simulate()
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn test_mc_fixed() {
+        // with no uncertainty every trial is the deterministic npv and irr
+        let values = vec![
+            Distribution::Fixed(-150000.0),
+            Distribution::Fixed(15000.0),
+            Distribution::Fixed(25000.0),
+            Distribution::Fixed(35000.0),
+            Distribution::Fixed(45000.0),
+            Distribution::Fixed(60000.0),
+        ];
+        let mc = MonteCarlo::from_tuple((values, Distribution::Fixed(0.05), 10, 1));
+        let res = mc.get().unwrap();
+        let npv = NetPresentValue::from_tuple((
+            vec![-150000.0, 15000.0, 25000.0, 35000.0, 45000.0, 60000.0],
+            0.05,
+        ))
+        .get()
+        .unwrap();
+        assert!(float_close(res.npv.mean, npv, RTOL, ATOL));
+        assert!(float_close(res.npv.std_dev, 0.0, RTOL, ATOL));
+        assert_eq!(res.prob_negative_npv, 0.0);
+
+        // npf.irr([-150000, 15000, 25000, 35000, 45000, 60000])
+        // 0.052432888859413884
+        let tgt = 0.052432888859413884;
+        let res = res.irr.percentile(50.0).unwrap();
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_mc_reproducible() {
+        let values = vec![
+            Distribution::Fixed(-1000.0),
+            Distribution::Normal(400.0, 50.0),
+            Distribution::LogNormal(6.0, 0.1),
+            Distribution::Triangular(300.0, 450.0, 500.0),
+            Distribution::Empirical(vec![100.0, 200.0, 300.0]),
+        ];
+        let rate = Distribution::Uniform(0.05, 0.1);
+        let a = MonteCarlo::from_tuple((values.clone(), rate.clone(), 200, 42))
+            .get()
+            .unwrap();
+        let b = MonteCarlo::from_tuple((values.clone(), rate.clone(), 200, 42))
+            .get()
+            .unwrap();
+        let c = MonteCarlo::from_tuple((values, rate, 200, 43))
+            .get()
+            .unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_mc_moments() {
+        let mut rng = Rng::new(7);
+        let n = 100000;
+        let normal = Distribution::Normal(2.0, 3.0);
+        let s = Summary::from_samples((0..n).map(|_| normal.sample(&mut rng)).collect());
+        assert!((s.mean - 2.0).abs() < 0.05, "{}", s.mean);
+        assert!((s.std_dev - 3.0).abs() < 0.05, "{}", s.std_dev);
+
+        // mean of triangular is (min + mode + max) / 3
+        let triangular = Distribution::Triangular(0.0, 3.0, 6.0);
+        let s = Summary::from_samples((0..n).map(|_| triangular.sample(&mut rng)).collect());
+        assert!((s.mean - 3.0).abs() < 0.05, "{}", s.mean);
+        assert!(s.samples[0] >= 0.0 && s.samples[n - 1] <= 6.0);
+
+        let uniform = Distribution::Uniform(-1.0, 1.0);
+        let s = Summary::from_samples((0..n).map(|_| uniform.sample(&mut rng)).collect());
+        assert!(s.mean.abs() < 0.05, "{}", s.mean);
+        assert!((s.percentile(75.0).unwrap() - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_mc_percentile() {
+        let s = Summary::from_samples(vec![3.0, 1.0, 2.0, 4.0]);
+        assert_eq!(s.percentile(0.0), Some(1.0));
+        assert_eq!(s.percentile(50.0), Some(2.5));
+        assert_eq!(s.percentile(100.0), Some(4.0));
+        assert_eq!(s.percentile(101.0), None);
+    }
+
+    #[test]
+    fn test_mc_err() {
+        let values = vec![
+            Distribution::Fixed(-1000.0),
+            Distribution::Uniform(1.0, 0.0),
+        ];
+        let mc = MonteCarlo::from_tuple((values, Distribution::Fixed(0.05), 10, 1));
+        assert!(mc.get().is_err());
+
+        let values = vec![Distribution::Fixed(-1000.0), Distribution::Fixed(1100.0)];
+        let mc = MonteCarlo::from_tuple((values, Distribution::Fixed(0.05), 0, 1));
+        assert!(mc.get().is_err());
+    }
+}
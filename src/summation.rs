@@ -0,0 +1,149 @@
+// `abs` and `mul_add` are inherent in `core` since Rust 1.85
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use crate::math::Float;
+
+/// # How the discounted terms of a cash flow are added up
+///
+/// * `Horner` - evaluate the cash flow as a polynomial in the discount factor by Horner's scheme, with one
+///   multiplication and one addition per period and no `powf`
+/// * `Compensated` - carry the rounding error of every product and addition along, i.e. Kahan-Babuška-Neumaier
+///   summation for plain sums and the compensated Horner scheme for polynomials, so that the result is as accurate as
+///   if computed in twice the working precision; a few more operations per period for long series or cash flows of
+///   mixed magnitude that cancel out
+///
+/// Set with `with_summation` of `NetPresentValue`, `InternalRateReturn` and `ModifiedIRR`, by `Horner` if not set
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// // 1e16 swallows 1.0 unless the rounding error is carried along
+/// let terms = [1e16, 1.0, -1e16, 1.0];
+/// assert_eq!(Summation::Horner.sum(terms), 1.0);
+/// assert_eq!(Summation::Compensated.sum(terms), 2.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Summation {
+    #[default]
+    Horner,
+    Compensated,
+}
+
+// `a + b` and its rounding error, exactly
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let err = if a.abs() >= b.abs() {
+        (a - s) + b
+    } else {
+        (b - s) + a
+    };
+    (s, err)
+}
+
+// `a * b` and its rounding error, exactly
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+/// The discount factor `1 / (1 + rate)` as a sum of a leading and a trailing `f64`, so that neither `1 + rate` nor
+/// the division loses the low-order bits of `rate`
+pub(crate) fn discount(rate: f64) -> (f64, f64) {
+    let (base, base_err) = two_sum(1.0, rate);
+    let d = 1.0 / base;
+    // 1 - d * (base + base_err), divided by base
+    let residual = (-d).mul_add(base, 1.0) - d * base_err;
+    (d, residual / base)
+}
+
+impl Summation {
+    /// Add up `terms`, in turn with `Horner` and with Neumaier's compensation with `Compensated`
+    pub fn sum<I: IntoIterator<Item = f64>>(self, terms: I) -> f64 {
+        match self {
+            Summation::Horner => terms.into_iter().sum(),
+            Summation::Compensated => {
+                let (sum, err) = terms.into_iter().fold((0.0, 0.0), |(sum, err), term| {
+                    let (sum, e) = two_sum(sum, term);
+                    (sum, err + e)
+                });
+                sum + err
+            }
+        }
+    }
+
+    /// Evaluate the polynomial `coefs[0] + coefs[1] * x + coefs[2] * x^2 + ...` at `x + x_err`, where `x_err` is a
+    /// correction below the precision of `x`, only used by `Compensated`
+    pub(crate) fn polyval<I>(self, coefs: I, x: f64, x_err: f64) -> f64
+    where
+        I: DoubleEndedIterator<Item = f64>,
+    {
+        match self {
+            Summation::Horner => coefs.rev().fold(0.0, |acc, c| acc * x + c),
+            Summation::Compensated => {
+                let (acc, err) = coefs.rev().fold((0.0, 0.0), |(acc, err), c| {
+                    let (p, p_err) = two_prod(acc, x);
+                    let (next, s_err) = two_sum(p, c);
+                    // the errors of this step, plus those of the previous ones times `x`
+                    (next, err * x + (p_err + s_err + acc * x_err))
+                });
+                acc + err
+            }
+        }
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn test_summation_sum() {
+        // 0.1 is not exact in binary, which piles up over 10,000 terms
+        // >>> math.fsum([0.1] * 10_000)
+        // 1000.0
+        let res = Summation::Compensated.sum([0.1; 10_000]);
+        assert_eq!(res, 1000.0);
+        let res = Summation::Horner.sum([0.1; 10_000]);
+        assert_ne!(res, 1000.0);
+    }
+
+    #[test]
+    fn test_summation_polyval() {
+        // 1 - 2x + 3x^2 at x = 2
+        let coefs = [1.0, -2.0, 3.0];
+        assert_eq!(Summation::Horner.polyval(coefs.into_iter(), 2.0, 0.0), 9.0);
+        let res = Summation::Compensated.polyval(coefs.into_iter(), 2.0, 0.0);
+        assert_eq!(res, 9.0);
+        assert_eq!(Summation::Horner.polyval([].into_iter(), 2.0, 0.0), 0.0);
+
+        // (x - 1)^3 = -1 + 3x - 3x^2 + x^3 next to its triple root, where Horner's scheme is all rounding error
+        // >>> from fractions import Fraction as F
+        // >>> float((F(1.0001) - 1) ** 3)
+        let coefs = [-1.0, 3.0, -3.0, 1.0];
+        let tgt = 9.999999999996696e-13;
+        let res = Summation::Compensated.polyval(coefs.into_iter(), 1.0001, 0.0);
+        assert!(
+            float_close(res, tgt, 1e-12, 0.0),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+        let res = Summation::Horner.polyval(coefs.into_iter(), 1.0001, 0.0);
+        assert!(
+            !float_close(res, tgt, 1e-6, 0.0),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_summation_discount() {
+        // 1 + 1e-17 rounds to 1, but the discount factor keeps track of it
+        let (d, d_err) = discount(1e-17);
+        assert_eq!(d, 1.0);
+        assert!(float_close(d_err, -1e-17, 1e-15, 0.0), "{:#?}", d_err);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{compare::npv_roots, NetPresentValue, Result};
+#[cfg(feature = "std")]
+use crate::{get_vecf64, Error, ParaMap, ParaType};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// # A table of `f64` with named columns
+/// Missing cells, e.g. where a calculator has no solution, are `NaN` and exported as empty
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    /// column names
+    pub columns: Vec<String>,
+    /// rows of cells, each of the same length as `columns`
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl Table {
+    /// Export as comma-separated values with a header line
+    pub fn to_csv(&self) -> String {
+        let mut csv = self.columns.join(",");
+        csv.push('\n');
+        for row in self.rows.iter() {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|c| {
+                    if c.is_nan() {
+                        String::new()
+                    } else {
+                        c.to_string()
+                    }
+                })
+                .collect();
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// # Compute the net present value of a cash flow over a grid of rates
+///
+/// ## Parameters
+/// * `values`: a cash flow, assume first payment is made at present, i.e. `t=0` the begining of 1st period
+/// * `rates` : the rates to evaluate `npv` at
+///
+/// ## Return:
+/// * `profile`: a [`Table`] of `rate` and `npv`, one row for each of `rates` in their order
+/// * `roots`: the `irr` roots within the range of `rates`, in ascending order
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let profile = NpvProfile::from_tuple((vec![-1000.0, 300.0, 400.0, 500.0], vec![0.0, 0.05, 0.1, 0.15]));
+/// println!("{:#?}'s profile is\n{}", profile, profile.get().unwrap().to_csv());
+/// println!("{:#?}'s roots are {:?}", profile, profile.get_roots());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NpvProfile {
+    values: Vec<f64>,
+    rates: Vec<f64>,
+}
+
+impl NpvProfile {
+    /// Instantiate a `NpvProfile` instance from a tuple of (`values`, `rates`) in said order
+    pub fn from_tuple(tup: (Vec<f64>, Vec<f64>)) -> Self {
+        NpvProfile {
+            values: tup.0,
+            rates: tup.1,
+        }
+    }
+
+    /// Instantiate a `NpvProfile` instance from a hash map with keys of (`values`, `rates`) in said order
+    /// Since [`HashMap`] requires values of same type, we need to wrap into a variant of enum
+    #[cfg(feature = "std")]
+    pub fn from_map(map: ParaMap) -> Result<Self> {
+        let op = |err: Error| {
+            Error::OtherError(format!(
+                "Failed construct an instance of `NpvProfile` from: `{:?}` <- {}",
+                map, err
+            ))
+        };
+        let values = get_vecf64(&map, "values").map_err(op)?;
+        let rates = get_vecf64(&map, "rates").map_err(op)?;
+        Ok(NpvProfile { values, rates })
+    }
+
+    fn profile(&self) -> Result<Table> {
+        let mut rows = Vec::with_capacity(self.rates.len());
+        for &rate in self.rates.iter() {
+            let npv = NetPresentValue::from_slice((&self.values, rate)).get()?;
+            rows.push(vec![rate, npv]);
+        }
+
+        Ok(Table {
+            columns: vec!["rate".into(), "npv".into()],
+            rows,
+        })
+    }
+
+    /// Get the npv profile from an instance of `NpvProfile`
+    pub fn get(&self) -> Result<Table> {
+        self.profile()
+    }
+
+    /// Get the irr roots within the range of `rates` from an instance of `NpvProfile`
+    pub fn get_roots(&self) -> Result<Vec<f64>> {
+        let lo = self.rates.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = self.rates.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut roots = npv_roots(&self.values)?;
+        roots.retain(|root| (lo..=hi).contains(root));
+        Ok(roots)
+    }
+}
+
+/// # Evaluate a calculator over a grid of two inputs
+/// This builds two-way tables such as `rate` x `nper` -> `pmt`, starting from a base [`ParaMap`] and replacing two of
+/// its parameters in each cell
+///
+/// ## Parameters
+/// * `map` : the base parameters of the calculator
+/// * `row_field` : the parameter varied along rows
+/// * `row_values` : the values of `row_field`
+/// * `col_field` : the parameter varied along columns
+/// * `col_values` : the values of `col_field`
+///
+/// ## Return:
+/// * `table`: a [`Table`] whose first column is `row_field` and other columns are named `col_field=value`
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let mut map = ParaMap::new();
+/// map.insert("pv".into(), ParaType::F64(250000.0));
+/// map.insert("fv".into(), ParaType::F64(0.0));
+/// map.insert("when".into(), ParaType::When(WhenType::End));
+/// let table = SensitivityTable::from_tuple((
+///     map,
+///     "rate".into(),
+///     vec![ParaType::F64(0.04 / 12.0), ParaType::F64(0.05 / 12.0)],
+///     "nper".into(),
+///     vec![ParaType::U32(180), ParaType::U32(360)],
+/// ));
+/// let res = table.get(|map| Payment::from_map(map)?.get()).unwrap();
+/// println!("{}", res.to_csv());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SensitivityTable {
+    map: ParaMap,
+    row_field: String,
+    row_values: Vec<ParaType>,
+    col_field: String,
+    col_values: Vec<ParaType>,
+}
+
+/// Label of a grid value in a [`Table`]
+#[cfg(feature = "std")]
+fn label(field: &str, value: &ParaType) -> Result<f64> {
+    match value {
+        ParaType::F64(v) => Ok(*v),
+        ParaType::U32(v) => Ok(*v as f64),
+        ParaType::When(v) => Ok(v.offset()),
+        ParaType::I64(v) => Ok(*v as f64),
+        _ => Err(Error::ParaError(format!(
+            "{}: f64, u32, i64 or WhenType",
+            field
+        ))),
+    }
+}
+
+#[cfg(feature = "std")]
+impl SensitivityTable {
+    /// Instantiate a `SensitivityTable` instance from a tuple of (`map`, `row_field`, `row_values`, `col_field`,
+    /// `col_values`) in said order
+    pub fn from_tuple(tup: (ParaMap, String, Vec<ParaType>, String, Vec<ParaType>)) -> Self {
+        SensitivityTable {
+            map: tup.0,
+            row_field: tup.1,
+            row_values: tup.2,
+            col_field: tup.3,
+            col_values: tup.4,
+        }
+    }
+
+    /// Get the table from an instance of `SensitivityTable`, where `calc` computes one cell from a [`ParaMap`].
+    /// Calculators returning `Option` should map `None` to `f64::NAN`
+    pub fn get<F>(&self, calc: F) -> Result<Table>
+    where
+        F: Fn(ParaMap) -> Result<f64>,
+    {
+        let mut columns = vec![self.row_field.clone()];
+        for value in self.col_values.iter() {
+            let v = label(&self.col_field, value)?;
+            columns.push(format!("{}={}", self.col_field, v));
+        }
+
+        let mut rows = Vec::with_capacity(self.row_values.len());
+        for row_value in self.row_values.iter() {
+            let mut row = vec![label(&self.row_field, row_value)?];
+            for col_value in self.col_values.iter() {
+                let mut map = self.map.clone();
+                map.insert(self.row_field.clone(), row_value.clone());
+                map.insert(self.col_field.clone(), col_value.clone());
+                row.push(calc(map)?);
+            }
+            rows.push(row);
+        }
+
+        Ok(Table { columns, rows })
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_npv_profile_from_tuple() {
+        // npf.irr([-1000, 300, 400, 500])
+        // 0.0889633947789738
+        let profile =
+            NpvProfile::from_tuple((vec![-1000.0, 300.0, 400.0, 500.0], vec![0.0, 0.05, 0.1]));
+        let res = profile.get().unwrap();
+        assert_eq!(res.columns, vec!["rate", "npv"]);
+        assert_eq!(res.rows.len(), 3);
+        assert_eq!(res.rows[0], vec![0.0, 200.0]);
+
+        let npv = NetPresentValue::from_tuple((vec![-1000.0, 300.0, 400.0, 500.0], 0.1))
+            .get()
+            .unwrap();
+        assert_eq!(res.rows[2], vec![0.1, npv]);
+
+        // the root between 0.05 and 0.1
+        let roots = profile.get_roots().unwrap();
+        let tgt = 0.0889633947789738;
+        assert!(
+            roots.len() == 1 && float_close(roots[0], tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            roots,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_npv_profile_from_map() {
+        let mut map = ParaMap::new();
+        map.insert(
+            "values".to_string(),
+            ParaType::VecF64(vec![-1000.0, 300.0, 400.0, 500.0]),
+        );
+        map.insert("rates".to_string(), ParaType::VecF64(vec![0.0, 0.05]));
+        let profile = NpvProfile::from_map(map).unwrap();
+        // the root lies outside of rates
+        assert!(profile.get_roots().unwrap().is_empty());
+        assert_eq!(profile.get().unwrap().rows.len(), 2);
+    }
+
+    #[test]
+    fn test_npv_profile_err() {
+        let mut map = ParaMap::new();
+        map.insert("rates".to_string(), ParaType::VecF64(vec![0.0, 0.05]));
+        assert!(NpvProfile::from_map(map).is_err());
+    }
+
+    #[test]
+    fn test_sensitivity_table() {
+        let mut map = ParaMap::new();
+        map.insert("pv".into(), ParaType::F64(15000.0));
+        map.insert("fv".into(), ParaType::F64(0.0));
+        map.insert("when".into(), ParaType::When(WhenType::End));
+        let table = SensitivityTable::from_tuple((
+            map,
+            "rate".into(),
+            vec![ParaType::F64(0.0), ParaType::F64(0.08 / 12.0)],
+            "nper".into(),
+            vec![ParaType::U32(60), ParaType::U32(120)],
+        ));
+        let res = table.get(|map| Payment::from_map(map)?.get()).unwrap();
+        assert_eq!(res.columns, vec!["rate", "nper=60", "nper=120"]);
+        assert_eq!(res.rows[0], vec![0.0, -250.0, -125.0]);
+
+        // npf.pmt(0.08 / 12, 60, 15000)
+        // -304.145914
+        let res = res.rows[1][1];
+        let tgt = -304.145914;
+        assert!(
+            float_close(res, tgt, RTOL, ATOL),
+            "{:#?} v.s. {:#?}",
+            res,
+            tgt
+        );
+    }
+
+    #[test]
+    fn test_sensitivity_table_err() {
+        let mut map = ParaMap::new();
+        map.insert("pv".into(), ParaType::F64(15000.0));
+        let table = SensitivityTable::from_tuple((
+            map,
+            "rate".into(),
+            vec![ParaType::F64(0.0)],
+            "nper".into(),
+            vec![ParaType::U32(60)],
+        ));
+        // `fv` and `when` are missing
+        assert!(table.get(|map| Payment::from_map(map)?.get()).is_err());
+    }
+
+    #[test]
+    fn test_table_to_csv() {
+        let table = Table {
+            columns: vec!["rate".into(), "nper".into()],
+            rows: vec![vec![0.05, 12.5], vec![0.1, f64::NAN]],
+        };
+        let res = table.to_csv();
+        let tgt = "rate,nper\n0.05,12.5\n0.1,\n";
+        assert_eq!(res, tgt);
+    }
+}
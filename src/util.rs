@@ -1,3 +1,7 @@
+// `abs` is inherent in `core` since Rust 1.85
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use crate::math::Float;
 use crate::{Error, Result};
 /// Tolerance of relative difference
 pub const RTOL: f64 = 1e-10;
@@ -12,52 +16,495 @@ pub fn float_close(lhs: f64, rhs: f64, rtol: f64, atol: f64) -> bool {
     cond1 | cond2
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[repr(u8)]
 /// when payments are due in a payment period
+///
+/// A payment due an `offset` of a period before its end grows by `(1 + rate)^offset` until the end of the period,
+/// i.e. by `1 + rate * when` of `numpy_financial` with `End = 0` and `Begin = 1`. Since `Offset` holds a value, a
+/// `WhenType` is not cast with `as`, but [`WhenType::offset`] gets its number.
 pub enum WhenType {
+    #[default]
     End = 0,
     Begin = 1,
+    /// in the middle of the period, as in the mid-period convention of discounted cash flow valuations
+    Middle = 2,
+    /// another offset of a period before its end, built by [`WhenType::from_offset`]
+    Offset(PeriodOffset) = 3,
+}
+
+/// An offset in `(0, 1)` other than `0.5` of [`WhenType::Offset`], so that each offset has one `WhenType`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PeriodOffset(f64);
+
+impl WhenType {
+    /// Fraction of a period before its end that payments are due, `0` for `End` and `1` for `Begin`
+    pub fn offset(&self) -> f64 {
+        match self {
+            WhenType::End => 0.0,
+            WhenType::Begin => 1.0,
+            WhenType::Middle => 0.5,
+            WhenType::Offset(offset) => offset.0,
+        }
+    }
+
+    /// Instantiate a `WhenType` from an `offset` in `[0, 1]`, one of `End`, `Middle` and `Begin` if it is one of
+    /// theirs, or `None` out of range
+    pub fn from_offset(offset: f64) -> Option<Self> {
+        match offset {
+            0.0 => Some(WhenType::End),
+            0.5 => Some(WhenType::Middle),
+            1.0 => Some(WhenType::Begin),
+            _ if offset > 0.0 && offset < 1.0 => Some(WhenType::Offset(PeriodOffset(offset))),
+            _ => None,
+        }
+    }
+
+    /// Growth of a payment from when it is due to the end of its period, i.e. `(1 + rate)^offset`
+    pub fn growth(&self, rate: f64) -> f64 {
+        match self {
+            WhenType::End => 1.0,
+            WhenType::Begin => 1.0 + rate,
+            _ => (1.0 + rate).powf(self.offset()),
+        }
+    }
+}
+
+/// `"end"`/`"finish"`, `"begin"`/`"start"` or `"middle"`/`"mid"` in any case, as `numpy_financial` accepts the
+/// first two, or an offset in `[0, 1]` such as `"0.25"`
+impl core::str::FromStr for WhenType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let is = |names: [&str; 2]| names.iter().any(|name| s.eq_ignore_ascii_case(name));
+        if is(["end", "finish"]) {
+            Ok(WhenType::End)
+        } else if is(["begin", "start"]) {
+            Ok(WhenType::Begin)
+        } else if is(["middle", "mid"]) {
+            Ok(WhenType::Middle)
+        } else {
+            s.parse()
+                .ok()
+                .and_then(WhenType::from_offset)
+                .ok_or_else(|| {
+                    // a `String` with `alloc`
+                    #[allow(clippy::useless_conversion)]
+                    Error::ParaError("when: end, begin, middle or an offset in [0, 1]".into())
+                })
+        }
+    }
 }
 
 /// Parameter types in a enum
-#[derive(Debug)]
+///
+/// The getters below coerce a value of another variant where nothing is lost, e.g. `F64(10.0)` or `I64(10)` for a
+/// `u32` and `"begin"` or `1` for a `WhenType`, and fail otherwise
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ParaType {
     F64(f64),
     U32(u32),
     When(WhenType),
     VecF64(Vec<f64>),
+    I64(i64),
+    Bool(bool),
+    Str(String),
+    Date(Date),
+    VecWhen(Vec<WhenType>),
 }
 
+#[cfg(feature = "std")]
 pub type ParaMap = std::collections::HashMap<String, ParaType>;
 
+#[cfg(feature = "std")]
 pub fn get_f64(map: &ParaMap, field: &str) -> Result<f64> {
-    if let Some(&ParaType::F64(v)) = map.get(field) {
-        Ok(v)
-    } else {
-        Err(Error::ParaError(format!("{}: f64", field)))
+    match map.get(field) {
+        Some(&ParaType::F64(v)) => Ok(v),
+        Some(&ParaType::U32(v)) => Ok(v as f64),
+        // beyond 2^53 not every integer is a `f64`
+        Some(&ParaType::I64(v)) if v.unsigned_abs() <= 1 << f64::MANTISSA_DIGITS => Ok(v as f64),
+        _ => Err(Error::ParaError(format!("{}: f64", field))),
     }
 }
 
+#[cfg(feature = "std")]
 pub fn get_u32(map: &ParaMap, field: &str) -> Result<u32> {
-    if let Some(&ParaType::U32(v)) = map.get(field) {
+    match map.get(field) {
+        Some(&ParaType::U32(v)) => Ok(v),
+        Some(&ParaType::I64(v)) if u32::try_from(v).is_ok() => Ok(v as u32),
+        Some(&ParaType::F64(v)) if v >= 0.0 && v <= u32::MAX as f64 && v.fract() == 0.0 => {
+            Ok(v as u32)
+        }
+        _ => Err(Error::ParaError(format!("{}: u32", field))),
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn get_i64(map: &ParaMap, field: &str) -> Result<i64> {
+    match map.get(field) {
+        Some(&ParaType::I64(v)) => Ok(v),
+        Some(&ParaType::U32(v)) => Ok(v as i64),
+        // `i64::MAX as f64` rounds up to 2^63, which is out of range
+        Some(&ParaType::F64(v))
+            if v >= i64::MIN as f64 && v < i64::MAX as f64 && v.fract() == 0.0 =>
+        {
+            Ok(v as i64)
+        }
+        _ => Err(Error::ParaError(format!("{}: i64", field))),
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn get_when(map: &ParaMap, field: &str) -> Result<WhenType> {
+    let when = match map.get(field) {
+        Some(ParaType::When(v)) => Some(v.clone()),
+        Some(ParaType::Str(s)) => s.parse().ok(),
+        Some(&ParaType::U32(v)) => WhenType::from_offset(v as f64),
+        Some(&ParaType::I64(v)) => WhenType::from_offset(v as f64),
+        Some(&ParaType::F64(v)) => WhenType::from_offset(v),
+        _ => None,
+    };
+    when.ok_or_else(|| Error::ParaError(format!("{}: WhenType", field)))
+}
+
+#[cfg(feature = "std")]
+pub fn get_vecf64(map: &ParaMap, field: &str) -> Result<Vec<f64>> {
+    get_slicef64(map, field).map(<[f64]>::to_vec)
+}
+
+/// Borrow a `VecF64` out of the map rather than cloning it as [`get_vecf64`] does
+#[cfg(feature = "std")]
+pub fn get_slicef64<'a>(map: &'a ParaMap, field: &str) -> Result<&'a [f64]> {
+    if let Some(ParaType::VecF64(v)) = map.get(field) {
         Ok(v)
     } else {
-        Err(Error::ParaError(format!("{}: u32", field)))
+        Err(Error::ParaError(format!("{}: VecF64", field)))
     }
 }
 
-pub fn get_when(map: &ParaMap, field: &str) -> Result<WhenType> {
-    if let Some(&ParaType::When(ref v)) = map.get(field) {
-        Ok(v.clone())
+#[cfg(feature = "std")]
+pub fn get_vecwhen(map: &ParaMap, field: &str) -> Result<Vec<WhenType>> {
+    let err = || Error::ParaError(format!("{}: VecWhen", field));
+    match map.get(field) {
+        Some(ParaType::VecWhen(v)) => Ok(v.clone()),
+        Some(ParaType::VecF64(v)) => v
+            .iter()
+            .map(|&x| WhenType::from_offset(x).ok_or_else(err))
+            .collect(),
+        _ => Err(err()),
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn get_bool(map: &ParaMap, field: &str) -> Result<bool> {
+    if let Some(&ParaType::Bool(v)) = map.get(field) {
+        Ok(v)
     } else {
-        Err(Error::ParaError(format!("{}: WhenType", field)))
+        Err(Error::ParaError(format!("{}: bool", field)))
     }
 }
 
-pub fn get_vecf64(map: &ParaMap, field: &str) -> Result<Vec<f64>> {
-    if let Some(&ParaType::VecF64(ref v)) = map.get(field) {
-        Ok(v.clone())
+#[cfg(feature = "std")]
+pub fn get_str<'a>(map: &'a ParaMap, field: &str) -> Result<&'a str> {
+    if let Some(ParaType::Str(v)) = map.get(field) {
+        Ok(v)
     } else {
-        Err(Error::ParaError(format!("{}: VecF64", field)))
+        Err(Error::ParaError(format!("{}: string", field)))
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn get_date(map: &ParaMap, field: &str) -> Result<Date> {
+    match map.get(field) {
+        Some(&ParaType::Date(v)) => Ok(v),
+        Some(ParaType::Str(s)) => s.parse(),
+        _ => Err(Error::ParaError(format!("{}: Date", field))),
+    }
+    .map_err(|_| Error::ParaError(format!("{}: Date", field)))
+}
+
+/// Parse a value of `key=value` text, i.e. in turn
+/// * `"..."` : a `Str` as is
+/// * `true` or `false` : a `Bool`
+/// * a whole number : a `U32`, or an `I64` if negative or too large
+/// * any other number : a `F64`
+/// * `YYYY-MM-DD` : a `Date`
+/// * `[...]` : a `VecF64` of comma-separated numbers, or a `VecWhen` of `end` and `begin`
+/// * anything else : a `Str`, e.g. `begin` which [`get_when`] takes for a `WhenType`
+#[cfg(feature = "std")]
+impl std::str::FromStr for ParaType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            let items: Vec<&str> = inner
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .collect();
+            // numbers first, so that offsets such as `[0, 1]` stay a `VecF64`
+            let values = items.iter().map(|item| item.parse::<f64>());
+            if let Ok(values) = values.collect::<core::result::Result<_, _>>() {
+                return Ok(ParaType::VecF64(values));
+            }
+            return items
+                .iter()
+                .map(|item| item.parse::<WhenType>())
+                .collect::<Result<_>>()
+                .map(ParaType::VecWhen)
+                .map_err(|_| Error::ParaError(format!("not a list of numbers: {}", s)));
+        }
+        if let Some(inner) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+            return Ok(ParaType::Str(inner.to_string()));
+        }
+        let value = match s {
+            "true" => ParaType::Bool(true),
+            "false" => ParaType::Bool(false),
+            _ => {
+                if let Ok(v) = s.parse::<u32>() {
+                    ParaType::U32(v)
+                } else if let Ok(v) = s.parse::<i64>() {
+                    ParaType::I64(v)
+                } else if let Ok(v) = s.parse::<f64>() {
+                    ParaType::F64(v)
+                } else if let Ok(v) = s.parse::<Date>() {
+                    ParaType::Date(v)
+                } else {
+                    ParaType::Str(s.to_string())
+                }
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Parse a `ParaMap` from lines of `key=value`, or pairs separated by `;`, see [`ParaType`] for how values are
+/// parsed; blank lines and lines starting with `#` are skipped
+///
+/// ## Example
+/// ```rust
+/// use rfinancial::*;
+/// let map = parse_map("rate = 0.004\nnper = 360\npv = 250000\nwhen = begin").unwrap();
+/// let pmt = Payment::from_map(map);
+/// println!("pmt is {:?}", pmt);
+/// let map = parse_map("values = [-100, 60, 60]; rate = 0.05").unwrap();
+/// assert_eq!(map.get("values"), Some(&ParaType::VecF64(vec![-100.0, 60.0, 60.0])));
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_map(text: &str) -> Result<ParaMap> {
+    let mut map = ParaMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        for pair in line.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let err = |msg: &str| Error::ParaError(format!("line {}: {}: {}", i + 1, msg, pair));
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| err("expected key=value"))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(err("empty key"));
+            }
+            let value = value.parse().map_err(|_| err("invalid value"))?;
+            if map.insert(key.to_string(), value).is_some() {
+                return Err(err("duplicate key"));
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// A calendar date in the proleptic Gregorian calendar, written and parsed as `YYYY-MM-DD`
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+#[cfg(feature = "std")]
+impl Date {
+    /// Instantiate a `Date`, failing if the month or the day does not exist
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(Error::ParaError(format!(
+                "not a date: {:04}-{:02}-{:02}",
+                year, month, day
+            )));
+        }
+        Ok(Date { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Whether the date is the last day of its month
+    pub fn is_month_end(&self) -> bool {
+        self.day == days_in_month(self.year, self.month)
+    }
+
+    /// Whole calendar months from `start` to `self`, negative if `self` is earlier, ignoring the days
+    pub fn months_since(&self, start: &Date) -> i64 {
+        (self.year as i64 - start.year as i64) * 12 + self.month as i64 - start.month as i64
+    }
+}
+
+#[cfg(feature = "std")]
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::str::FromStr for Date {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let err = || Error::ParaError(format!("not a date: {}", s));
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().filter(|p| !p.is_empty()).ok_or_else(err);
+        let (year, month, day) = (next()?, next()?, next()?);
+        Date::new(
+            year.parse().map_err(|_| err())?,
+            month.parse().map_err(|_| err())?,
+            day.parse().map_err(|_| err())?,
+        )
+        .map_err(|_| err())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for Date {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(fmt, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_util_coercion() {
+        let map = ParaMap::from([
+            ("a".to_string(), ParaType::F64(10.0)),
+            ("b".to_string(), ParaType::F64(10.5)),
+            ("c".to_string(), ParaType::I64(-1)),
+            ("d".to_string(), ParaType::Str("Begin".to_string())),
+            ("e".to_string(), ParaType::U32(1)),
+            ("f".to_string(), ParaType::Str("2024-02-29".to_string())),
+        ]);
+        assert_eq!(get_u32(&map, "a").unwrap(), 10);
+        assert!(get_u32(&map, "b").is_err());
+        assert!(get_u32(&map, "c").is_err());
+        assert_eq!(get_i64(&map, "c").unwrap(), -1);
+        assert_eq!(get_f64(&map, "c").unwrap(), -1.0);
+        assert_eq!(get_when(&map, "d").unwrap(), WhenType::Begin);
+        assert_eq!(get_when(&map, "e").unwrap(), WhenType::Begin);
+        assert!(get_when(&map, "b").is_err());
+        assert_eq!(
+            get_date(&map, "f").unwrap(),
+            Date::new(2024, 2, 29).unwrap()
+        );
+        assert!(get_date(&map, "d").is_err());
+        assert!(get_f64(&map, "d").is_err());
+
+        // `nper` of a `f64` is taken as is
+        let mut map = ParaMap::new();
+        map.insert("rate".into(), ParaType::F64(0.075));
+        map.insert("nper".into(), ParaType::F64(20.0));
+        map.insert("pmt".into(), ParaType::F64(-2000.0));
+        map.insert("pv".into(), ParaType::U32(0));
+        map.insert("when".into(), ParaType::Str("end".to_string()));
+        let tgt = FutureValue::from_tuple((0.075, 20, -2000.0, 0.0, WhenType::End));
+        assert_eq!(FutureValue::from_map(map).unwrap(), tgt);
+    }
+
+    #[test]
+    fn test_util_when_from_str() {
+        for (s, tgt) in [
+            ("end", WhenType::End),
+            ("Finish", WhenType::End),
+            ("BEGIN", WhenType::Begin),
+            ("start", WhenType::Begin),
+            ("Mid", WhenType::Middle),
+            ("0.5", WhenType::Middle),
+            ("1", WhenType::Begin),
+        ] {
+            assert_eq!(s.parse::<WhenType>().unwrap(), tgt);
+        }
+        assert_eq!("0.25".parse::<WhenType>().unwrap().offset(), 0.25);
+        assert_eq!("0.0".parse::<WhenType>().unwrap(), WhenType::End);
+        assert!("later".parse::<WhenType>().is_err());
+        assert!("1.5".parse::<WhenType>().is_err());
+
+        // a list of names and offsets, while one of numbers only stays numbers
+        let tgt = ParaType::VecWhen(vec![WhenType::End, WhenType::Middle]);
+        assert_eq!("[End, 0.5]".parse::<ParaType>().unwrap(), tgt);
+        let tgt = ParaType::VecF64(vec![0.0, 1.0]);
+        assert_eq!("[0, 1]".parse::<ParaType>().unwrap(), tgt);
+    }
+
+    #[test]
+    fn test_util_parse_map() {
+        let text = "# a loan\nrate = 0.004; nper = 360\npv=-250000; fv = 0\n\nwhen = begin\n\
+                    start = 2024-01-31\nname = \"end\"\nfixed = true\nwhens = [end, begin]\n\
+                    values = [-100, 60.5, 6e1]\nempty = []";
+        let map = parse_map(text).unwrap();
+        assert_eq!(map.len(), 11);
+        assert_eq!(map["rate"], ParaType::F64(0.004));
+        assert_eq!(map["nper"], ParaType::U32(360));
+        assert_eq!(map["pv"], ParaType::I64(-250000));
+        assert_eq!(map["when"], ParaType::Str("begin".to_string()));
+        assert_eq!(
+            map["start"],
+            ParaType::Date(Date::new(2024, 1, 31).unwrap())
+        );
+        assert_eq!(map["name"], ParaType::Str("end".to_string()));
+        assert_eq!(map["fixed"], ParaType::Bool(true));
+        let whens = vec![WhenType::End, WhenType::Begin];
+        assert_eq!(map["whens"], ParaType::VecWhen(whens));
+        assert_eq!(map["values"], ParaType::VecF64(vec![-100.0, 60.5, 60.0]));
+        assert_eq!(map["empty"], ParaType::VecF64(vec![]));
+
+        let res = Payment::from_map(map).unwrap().get().unwrap();
+        let tgt = Payment::from_tuple((0.004, 360, -250000.0, 0.0, WhenType::Begin))
+            .get()
+            .unwrap();
+        assert_eq!(res, tgt);
+
+        let err = |text: &str| match parse_map(text) {
+            Err(Error::ParaError(msg)) => msg,
+            res => panic!("{:?}", res),
+        };
+        assert_eq!(err("rate = 0.1\nnper"), "line 2: expected key=value: nper");
+        assert_eq!(
+            err("rate = 0.1; rate = 0.2"),
+            "line 1: duplicate key: rate = 0.2"
+        );
+        assert_eq!(err(" = 1"), "line 1: empty key: = 1");
+        assert_eq!(
+            err("values = [1, x]"),
+            "line 1: invalid value: values = [1, x]"
+        );
     }
 }
@@ -0,0 +1,339 @@
+//! # WebAssembly bindings
+//! JavaScript-friendly functions built with the `wasm` feature as a `cdylib`, see the README for the build steps.
+//! Arguments and results are plain numbers and typed arrays, with `camelCase` names in JavaScript.
+//! * optional arguments may be left `undefined`, `fv` defaulting to `0` and `when` to `0` (end of periods), `1` being the beginning
+//! * results without a solution are `NaN`, while invalid arguments throw an `Error`
+//!
+//! ## Example
+//! ```js
+//! import { pmt, amortize } from "rfinancial";
+//! const payment = pmt(0.05 / 12, 360, 250000);
+//! const schedule = amortize(0.05 / 12, 360, 250000);
+//! console.log(payment, schedule.balance);
+//! ```
+use crate::{
+    AdjustableRateLoan, AmortizationRow, Error, FutureValue, InterestPayment, InternalRateReturn,
+    ModifiedIRR, NetPresentValue, NumberPeriod, Payment, PresentValue, PrincipalPayment, Rate,
+    WhenType,
+};
+use wasm_bindgen::prelude::*;
+
+fn js_error(err: Error) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+fn when(when: Option<u8>) -> Result<WhenType, JsError> {
+    match when {
+        None | Some(0) => Ok(WhenType::End),
+        Some(1) => Ok(WhenType::Begin),
+        Some(w) => Err(JsError::new(&format!("invalid `when`: {}", w))),
+    }
+}
+
+/// A missing solution is `NaN` in JavaScript
+fn solution(res: crate::Result<Option<f64>>) -> Result<f64, JsError> {
+    Ok(res.map_err(js_error)?.unwrap_or(f64::NAN))
+}
+
+/// Future value, see [`FutureValue`]
+#[wasm_bindgen]
+pub fn fv(rate: f64, nper: u32, pmt: f64, pv: f64, when: Option<u8>) -> Result<f64, JsError> {
+    let tup = (rate, nper, pmt, pv, self::when(when)?);
+    FutureValue::from_tuple(tup).get().map_err(js_error)
+}
+
+/// Present value, see [`PresentValue`]
+#[wasm_bindgen]
+pub fn pv(
+    rate: f64,
+    nper: u32,
+    pmt: f64,
+    fv: Option<f64>,
+    when: Option<u8>,
+) -> Result<f64, JsError> {
+    let tup = (rate, nper, pmt, fv.unwrap_or(0.0), self::when(when)?);
+    PresentValue::from_tuple(tup).get().map_err(js_error)
+}
+
+/// Payment against loan principal plus interest, see [`Payment`]
+#[wasm_bindgen]
+pub fn pmt(
+    rate: f64,
+    nper: u32,
+    pv: f64,
+    fv: Option<f64>,
+    when: Option<u8>,
+) -> Result<f64, JsError> {
+    let tup = (rate, nper, pv, fv.unwrap_or(0.0), self::when(when)?);
+    Payment::from_tuple(tup).get().map_err(js_error)
+}
+
+/// Number of periodic payments, see [`NumberPeriod`]
+#[wasm_bindgen]
+pub fn nper(
+    rate: f64,
+    pmt: f64,
+    pv: f64,
+    fv: Option<f64>,
+    when: Option<u8>,
+) -> Result<f64, JsError> {
+    let tup = (rate, pmt, pv, fv.unwrap_or(0.0), self::when(when)?);
+    solution(NumberPeriod::from_tuple(tup).get())
+}
+
+/// Interest portion of payment `per`, see [`InterestPayment`]
+#[wasm_bindgen]
+pub fn ipmt(
+    rate: f64,
+    per: u32,
+    nper: u32,
+    pv: f64,
+    fv: Option<f64>,
+    when: Option<u8>,
+) -> Result<f64, JsError> {
+    let tup = (rate, per, nper, pv, fv.unwrap_or(0.0), self::when(when)?);
+    solution(InterestPayment::from_tuple(tup).get())
+}
+
+/// Principal portion of payment `per`, see [`PrincipalPayment`]
+#[wasm_bindgen]
+pub fn ppmt(
+    rate: f64,
+    per: u32,
+    nper: u32,
+    pv: f64,
+    fv: Option<f64>,
+    when: Option<u8>,
+) -> Result<f64, JsError> {
+    let tup = (rate, per, nper, pv, fv.unwrap_or(0.0), self::when(when)?);
+    solution(PrincipalPayment::from_tuple(tup).get())
+}
+
+/// Rate of interest per period, see [`Rate`]. `guess` defaults to `0.1`
+#[wasm_bindgen]
+pub fn rate(
+    nper: u32,
+    pmt: f64,
+    pv: f64,
+    fv: f64,
+    when: Option<u8>,
+    guess: Option<f64>,
+) -> Result<f64, JsError> {
+    let tup = (
+        nper,
+        pmt,
+        pv,
+        fv,
+        self::when(when)?,
+        guess.unwrap_or(0.1),
+        1e-6,
+        100,
+    );
+    solution(Rate::from_tuple(tup).get())
+}
+
+/// Internal rate of return of a `Float64Array` of cash flows, see [`InternalRateReturn`]
+#[wasm_bindgen]
+pub fn irr(values: &[f64]) -> Result<f64, JsError> {
+    solution(InternalRateReturn::from_slice(values).get())
+}
+
+/// Net present value of a `Float64Array` of cash flows, see [`NetPresentValue`]
+#[wasm_bindgen]
+pub fn npv(rate: f64, values: &[f64]) -> Result<f64, JsError> {
+    let tup = (values, rate);
+    NetPresentValue::from_slice(tup).get().map_err(js_error)
+}
+
+/// Modified internal rate of return of a `Float64Array` of cash flows, see [`ModifiedIRR`]
+#[wasm_bindgen]
+pub fn mirr(values: &[f64], finance_rate: f64, reinvest_rate: f64) -> Result<f64, JsError> {
+    let tup = (values, finance_rate, reinvest_rate);
+    solution(ModifiedIRR::from_slice(tup).get())
+}
+
+/// Amortization schedule as columns of typed arrays, one element for each period
+#[wasm_bindgen]
+pub struct Amortization {
+    rows: Vec<AmortizationRow>,
+}
+
+#[wasm_bindgen]
+impl Amortization {
+    /// Number of periods
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Payment periods, starting from 1
+    #[wasm_bindgen(getter)]
+    pub fn per(&self) -> Vec<u32> {
+        self.rows.iter().map(|r| r.per).collect()
+    }
+
+    /// Interest rate applied in each period
+    #[wasm_bindgen(getter)]
+    pub fn rate(&self) -> Vec<f64> {
+        self.rows.iter().map(|r| r.rate).collect()
+    }
+
+    /// Total payment of each period
+    #[wasm_bindgen(getter)]
+    pub fn pmt(&self) -> Vec<f64> {
+        self.rows.iter().map(|r| r.pmt).collect()
+    }
+
+    /// Interest portion of each payment
+    #[wasm_bindgen(getter)]
+    pub fn ipmt(&self) -> Vec<f64> {
+        self.rows.iter().map(|r| r.ipmt).collect()
+    }
+
+    /// Principal portion of each payment
+    #[wasm_bindgen(getter)]
+    pub fn ppmt(&self) -> Vec<f64> {
+        self.rows.iter().map(|r| r.ppmt).collect()
+    }
+
+    /// Remaining principal after each payment
+    #[wasm_bindgen(getter)]
+    pub fn balance(&self) -> Vec<f64> {
+        self.rows.iter().map(|r| r.balance).collect()
+    }
+}
+
+/// Amortization schedule of a fixed-rate loan
+#[wasm_bindgen]
+pub fn amortize(
+    rate: f64,
+    nper: u32,
+    pv: f64,
+    fv: Option<f64>,
+    when: Option<u8>,
+) -> Result<Amortization, JsError> {
+    amortize_adjustable(
+        rate,
+        nper,
+        pv,
+        fv,
+        when,
+        nper,
+        1,
+        &[],
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Amortization schedule of an adjustable-rate loan, see [`AdjustableRateLoan`]. `index` is a `Float64Array`,
+/// `margin` defaults to `0`, and missing caps and floor to no limit
+#[wasm_bindgen(js_name = amortizeAdjustable)]
+#[allow(clippy::too_many_arguments)]
+pub fn amortize_adjustable(
+    rate: f64,
+    nper: u32,
+    pv: f64,
+    fv: Option<f64>,
+    when: Option<u8>,
+    first_reset: u32,
+    reset_freq: u32,
+    index: &[f64],
+    margin: Option<f64>,
+    periodic_cap: Option<f64>,
+    lifetime_cap: Option<f64>,
+    lifetime_floor: Option<f64>,
+) -> Result<Amortization, JsError> {
+    let arm = AdjustableRateLoan::from_tuple((
+        rate,
+        nper,
+        pv,
+        fv.unwrap_or(0.0),
+        self::when(when)?,
+        first_reset,
+        reset_freq,
+        index.to_vec(),
+        margin.unwrap_or(0.0),
+        periodic_cap.unwrap_or(f64::INFINITY),
+        lifetime_cap.unwrap_or(f64::INFINITY),
+        lifetime_floor.unwrap_or(f64::NEG_INFINITY),
+    ));
+    let rows = arm.get().map_err(js_error)?;
+    Ok(Amortization { rows })
+}
+
+// `JsError` can only be built on wasm targets, so only successful calls are tested natively.
+// Run `wasm-pack test --node --features wasm` to test in a headless runtime.
+#[allow(unused_imports)]
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::*;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn test_wasm_tvm() {
+        // npf.pmt(0.075 / 12, 12 * 15, 200000)
+        let res = pmt(0.075 / 12.0, 12 * 15, 200000.0, None, None).unwrap();
+        let tgt = -1854.0247200054619;
+        assert!(float_close(res, tgt, RTOL, ATOL), "{} v.s. {}", res, tgt);
+
+        // npf.fv(0.075, 20, -2000, 0, 'begin')
+        let res = fv(0.075, 20, -2000.0, 0.0, Some(1)).unwrap();
+        let tgt = 93105.06487352126;
+        assert!(float_close(res, tgt, RTOL, ATOL), "{} v.s. {}", res, tgt);
+
+        // npf.rate(10, 0, -3500, 10000)
+        let res = rate(10, 0.0, -3500.0, 10000.0, None, None).unwrap();
+        let tgt = 0.11069085371426901;
+        assert!(float_close(res, tgt, RTOL, ATOL), "{} v.s. {}", res, tgt);
+
+        // ipmt of the 0th period has no solution
+        assert!(ipmt(0.01, 0, 24, 2000.0, None, None).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_wasm_cash_flows() {
+        let values = [-100.0, 39.0, 59.0, 55.0, 20.0];
+        let res = npv(0.281, &values).unwrap();
+        let tgt = NetPresentValue::from_tuple((values.to_vec(), 0.281))
+            .get()
+            .unwrap();
+        assert_eq!(res, tgt);
+
+        let res = irr(&values).unwrap();
+        let tgt = InternalRateReturn::from_vec(values.to_vec())
+            .get()
+            .unwrap()
+            .unwrap();
+        assert_eq!(res, tgt);
+
+        let res = mirr(&values, 0.1, 0.12).unwrap();
+        let tgt = ModifiedIRR::from_tuple((values.to_vec(), 0.1, 0.12))
+            .get()
+            .unwrap()
+            .unwrap();
+        assert_eq!(res, tgt);
+    }
+
+    #[test]
+    fn test_wasm_amortize() {
+        let schedule = amortize(0.05 / 12.0, 360, 250000.0, None, None).unwrap();
+        assert_eq!(schedule.length(), 360);
+        assert_eq!(schedule.per()[359], 360);
+
+        let tgt = pmt(0.05 / 12.0, 360, 250000.0, None, None).unwrap();
+        assert!(schedule
+            .pmt()
+            .iter()
+            .all(|&p| float_close(p, tgt, RTOL, ATOL)));
+
+        let last = schedule.balance()[359];
+        assert!(float_close(last, 0.0, RTOL, ATOL), "{}", last);
+        let (ipmt, ppmt) = (schedule.ipmt(), schedule.ppmt());
+        assert!(float_close(ipmt[0] + ppmt[0], tgt, RTOL, ATOL));
+    }
+}
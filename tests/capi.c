@@ -0,0 +1,77 @@
+// Tests of the C ABI, run by tests/capi.rs
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "rfinancial.h"
+
+static int failures = 0;
+
+static void check(const char *name, int cond) {
+    if (!cond) {
+        fprintf(stderr, "FAILED: %s\n", name);
+        failures++;
+    }
+}
+
+static int close(double res, double tgt) {
+    return fabs(res - tgt) <= 1e-5 + 1e-10 * fabs(tgt);
+}
+
+int main(void) {
+    double out;
+    bool has_value;
+
+    // npf.pmt(0.075 / 12, 12 * 15, 200000)
+    check("pmt", rf_pmt(0.075 / 12, 12 * 15, 200000, 0, 0, &out) == RF_OK);
+    check("pmt value", close(out, -1854.0247200054619));
+
+    // npf.fv(0.075, 20, -2000, 0, 'end')
+    check("fv", rf_fv(0.075, 20, -2000, 0, 0, &out) == RF_OK);
+    check("fv value", close(out, 86609.36267304296));
+
+    // npf.nper(0.07 / 12, -150, 8000)
+    check("nper", rf_nper(0.07 / 12, -150, 8000, 0, 0, &out, &has_value) == RF_OK);
+    check("nper value", has_value && close(out, 64.07334877066185));
+
+    // npf.rate(10, 0, -3500, 10000)
+    check("rate", rf_rate(10, 0, -3500, 10000, 0, 0.1, 1e-6, 100, &out, &has_value) == RF_OK);
+    check("rate value", has_value && close(out, 0.11069085371426901));
+
+    // npf.irr([-150000, 15000, 25000, 35000, 45000, 60000])
+    const double flows[] = {-150000, 15000, 25000, 35000, 45000, 60000};
+    check("irr", rf_irr(flows, 6, &out, &has_value) == RF_OK);
+    check("irr value", has_value && close(out, 0.052432888859413884));
+
+    const double values[] = {-100, 39, 59, 55, 20};
+
+    // npf.npv(0.281, [-100, 39, 59, 55, 20])
+    check("npv", rf_npv(values, 5, 0.281, &out) == RF_OK);
+    check("npv value", close(out, -0.0084785916384548798));
+
+    // no solution
+    check("ipmt", rf_ipmt(0.01, 0, 24, 2000, 0, 0, &out, &has_value) == RF_OK);
+    check("ipmt no value", !has_value && isnan(out));
+
+    // errors
+    check("invalid when", rf_fv(0.075, 20, -2000, 0, 2, &out) == RF_PARA_ERROR);
+    check("null out", rf_fv(0.075, 20, -2000, 0, 0, NULL) == RF_NULL_POINTER);
+    check("null values", rf_npv(NULL, 5, 0.1, &out) == RF_NULL_POINTER);
+
+    // amortization schedule, asking for the number of rows first
+    size_t written = 0;
+    check("arm size", rf_arm(0.01, 12, 1000, 0, 0, 12, 1, NULL, 0, 0, INFINITY, INFINITY,
+                             -INFINITY, NULL, 0, &written) == RF_BUFFER_TOO_SMALL);
+    check("arm rows", written == 12);
+    rf_amortization_row *rows = malloc(written * sizeof(rf_amortization_row));
+    check("arm", rf_arm(0.01, 12, 1000, 0, 0, 12, 1, NULL, 0, 0, INFINITY, INFINITY, -INFINITY,
+                        rows, written, &written) == RF_OK);
+    check("arm last period", rows[11].per == 12 && close(rows[11].balance, 0));
+    check("arm payment", close(rows[0].ipmt + rows[0].ppmt, rows[0].pmt));
+    free(rows);
+
+    if (failures == 0) {
+        printf("all C ABI tests passed\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,74 @@
+//! Tests of the C ABI from C, i.e. the generated header and a C program linked against the library
+#![cfg(feature = "capi")]
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn header_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("include/rfinancial.h")
+}
+
+#[test]
+fn test_capi_header_is_current() {
+    let header = rfinancial::capi::header();
+    if std::env::var_os("RFINANCIAL_BLESS").is_some() {
+        std::fs::write(header_path(), &header).unwrap();
+    }
+    let current = std::fs::read_to_string(header_path()).unwrap_or_default();
+    assert!(
+        current == header,
+        "`include/rfinancial.h` is out of date, run `RFINANCIAL_BLESS=1 cargo test --features capi`"
+    );
+}
+
+#[test]
+fn test_capi_c_program() {
+    // build the shared library in its own target directory, since the one of `cargo test` may be
+    // overwritten by builds without the `capi` feature
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("capi");
+    let status = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .arg("rustc")
+        .arg("--lib")
+        .arg("--features")
+        .arg("capi")
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .arg("--crate-type")
+        .arg("cdylib")
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to build the library");
+    let lib_dir = target_dir.join("debug");
+    let out = target_dir.join("capi_test");
+
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".into()))
+        .arg(manifest_dir.join("tests/capi.c"))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-lrfinancial")
+        .arg("-lm")
+        .arg("-o")
+        .arg(&out)
+        .status()
+        .expect("a C compiler is required, set `CC` to use another one");
+    assert!(status.success(), "failed to compile tests/capi.c");
+
+    // cargo puts its own target directory on the library path of tests
+    let output = Command::new(&out)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .env("DYLD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}